@@ -1,14 +1,26 @@
 // src-tauri/src/adblock_plugin.rs (corregido)
-use adblock::{lists::ParseOptions, request::Request, Engine};
-use std::collections::HashSet;
+use crate::adblock_stats::AdBlockStats;
+use crate::shutdown::ShutdownCoordinator;
+use adblock::{
+    blocker::BlockerResult,
+    lists::ParseOptions,
+    request::Request,
+    resources::{MimeType, Resource},
+    Engine,
+};
+use serde::Serialize;
+use std::collections::{HashMap, HashSet};
 use std::fs;
-use std::path::Path;
-use std::sync::{Arc, RwLock};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
 use std::time::{Duration, SystemTime, UNIX_EPOCH};
 use tauri::{
     plugin::{Builder, TauriPlugin},
-    AppHandle, Manager,
+    AppHandle, Emitter, Manager,
 };
+use tokio::sync::Semaphore;
+use tokio::task::JoinSet;
 
 const FILTER_LISTS: &[(&str, &str)] = &[
     ("uBlock filters", "https://raw.githubusercontent.com/uBlockOrigin/uAssets/master/filters/filters.txt"),
@@ -22,48 +34,240 @@ const FILTER_LISTS: &[(&str, &str)] = &[
     ("Peter Lowes Ad and tracking server list", "https://pgl.yoyo.org/adservers/serverlist.php?hostformat=hosts&showintro=0&mimetype=plaintext"),
 ];
 
+/// Compact rule set compiled into the binary so blocking works before (or without) any download.
+const BASELINE_FILTERS: &str = include_str!("./filters/baseline.txt");
+
+/// Subset of uBO's redirect resources (`$redirect=noopjs`, ...) that list fixes rely on.
+/// (name, aliases, mime, content)
+const REDIRECT_RESOURCES: &[(&str, &[&str], MimeType, &str)] = &[
+    (
+        "noop.js",
+        &["noopjs"],
+        MimeType::ApplicationJavascript,
+        "(function() {\n    'use strict';\n})();\n",
+    ),
+    ("noop.txt", &["nooptext"], MimeType::TextPlain, ""),
+    ("empty", &[], MimeType::TextPlain, ""),
+    (
+        "noop.html",
+        &["noopframe"],
+        MimeType::TextHtml,
+        "<!DOCTYPE html>\n<html><head></head><body></body></html>\n",
+    ),
+    ("noop.json", &["noopjson"], MimeType::ApplicationJson, "{}"),
+];
+
 const WHITELIST_DOMAINS: &[&str] = &[];
 const CACHE_DURATION_SECS: u64 = 24 * 60 * 60;
 const MAX_DOWNLOAD_SIZE: usize = 50 * 1024 * 1024;
+const MAX_PARALLEL_DOWNLOADS: usize = 4;
+const CONNECT_TIMEOUT_SECS: u64 = 10;
+/// Longest silence between two reads of a list.
+const READ_TIMEOUT_SECS: u64 = 30;
+/// Whole download of one list, enforced by the HTTP agent.
+const DOWNLOAD_TIMEOUT_SECS: u64 = 60;
+/// Backstop over the blocking download in case the agent's timeouts do not fire.
+const DOWNLOAD_BACKSTOP_SECS: u64 = DOWNLOAD_TIMEOUT_SECS + 5;
+const PROGRESS_EVENT: &str = "adblock:progress";
+const STATS_FILE: &str = "stats.json";
+const STATS_FLUSH_INTERVAL_SECS: u64 = 60;
+/// Serialized engine built from the lists; loading it skips parsing them on start.
 const ENGINE_CACHE_FILE: &str = "engine.dat";
 
+/// What the webview should do with a request: drop it, swap in a redirect
+/// resource (data URL) or load a rewritten URL (`$removeparam`).
+#[derive(Serialize, Default)]
+pub struct NetworkDecision {
+    blocked: bool,
+    redirect: Option<String>,
+    rewritten_url: Option<String>,
+}
+
+/// Where the rules of the serving engine can be read back from.
+#[derive(Clone)]
+enum RuleSource {
+    Baseline,
+    /// Cached list files, in `FILTER_LISTS` order.
+    Lists(Vec<PathBuf>),
+}
+
+impl RuleSource {
+    fn read(&self) -> String {
+        match self {
+            RuleSource::Baseline => BASELINE_FILTERS.to_string(),
+            RuleSource::Lists(paths) => paths
+                .iter()
+                .filter_map(|path| fs::read_to_string(path).ok())
+                .collect::<Vec<_>>()
+                .join("\n"),
+        }
+    }
+}
+
 #[derive(Clone)]
 pub struct AdBlockState {
     engine: Arc<RwLock<Option<Engine>>>,
+    rule_source: Arc<RwLock<RuleSource>>,
+    /// Debug build of the same rules, which keeps each rule's text. Only built
+    /// when a rule has to be named (`explain_url`, the stats log).
+    rule_engine: Arc<Mutex<Option<Engine>>>,
+    stats: Arc<Mutex<AdBlockStats>>,
+    disabled: Arc<AtomicBool>,
+    /// `engine.dat` path while the serving engine, built from the lists, is not on disk yet.
+    unsaved_engine: Arc<Mutex<Option<PathBuf>>>,
 }
 
 impl AdBlockState {
     fn new() -> Self {
         Self {
             engine: Arc::new(RwLock::new(None)),
+            rule_source: Arc::new(RwLock::new(RuleSource::Baseline)),
+            rule_engine: Arc::new(Mutex::new(None)),
+            stats: Arc::new(Mutex::new(AdBlockStats::default())),
+            disabled: Arc::new(AtomicBool::new(false)),
+            unsaved_engine: Arc::new(Mutex::new(None)),
         }
     }
 
-    fn set_engine(&self, engine: Engine) {
+    fn set_engine(&self, mut engine: Engine, source: RuleSource) {
+        engine.use_resources(redirect_resources());
         *self.engine.write().unwrap() = Some(engine);
+        *self.rule_source.write().unwrap() = source;
+        *self.rule_engine.lock().unwrap() = None;
+    }
+
+    /// The rules that decided a request, as `(filter, exception)`.
+    fn rule_names(
+        &self,
+        url: &str,
+        source_url: &str,
+        request_type: &str,
+    ) -> (Option<String>, Option<String>) {
+        let Ok(req) = Request::new(url, source_url, normalize_request_type(request_type)) else {
+            return (None, None);
+        };
+        let mut rule_engine = self.rule_engine.lock().unwrap();
+        let engine = rule_engine.get_or_insert_with(|| {
+            let source = self.rule_source.read().unwrap().clone();
+            rule_engine_from_filters(&source.read())
+        });
+        let result = engine.check_network_request(&req);
+        (result.filter, result.exception)
     }
 
     fn check_url(&self, url: &str, source_url: &str, request_type: &str) -> bool {
+        self.check_request(url, source_url, request_type).blocked
+    }
+
+    fn check_request(&self, url: &str, source_url: &str, request_type: &str) -> NetworkDecision {
+        if self.is_disabled() {
+            return NetworkDecision::default();
+        }
+
+        let Some(result) = self.evaluate(url, source_url, request_type) else {
+            return NetworkDecision::default();
+        };
+        self.stats.lock().unwrap().record(
+            url,
+            source_url,
+            normalize_request_type(request_type),
+            result.matched,
+        );
+        NetworkDecision {
+            blocked: result.matched,
+            redirect: result.redirect,
+            rewritten_url: result.rewritten_url,
+        }
+    }
+
+    fn evaluate(&self, url: &str, source_url: &str, request_type: &str) -> Option<BlockerResult> {
         if Self::is_whitelisted(url) || matches!(url.get(..6), Some("data:" | "blob:" | "tauri:")) {
-            return false;
+            return None;
         }
 
         let engine_guard = self.engine.read().unwrap();
-        let Some(engine) = engine_guard.as_ref() else {
-            return false;
+        let engine = engine_guard.as_ref()?;
+        let req = Request::new(url, source_url, normalize_request_type(request_type)).ok()?;
+        Some(engine.check_network_request(&req))
+    }
+
+    /// Full engine verdict for a request, for tracking down which rule breaks a page.
+    /// Ignores the session switch so a disabled blocker can still be inspected.
+    /// The first call builds the rule engine, so run it off the async runtime.
+    pub fn explain_url(
+        &self,
+        url: &str,
+        source_url: &str,
+        request_type: &str,
+    ) -> serde_json::Value {
+        let result = self.evaluate(url, source_url, request_type);
+        let (filter, exception) = match &result {
+            Some(_) => self.rule_names(url, source_url, request_type),
+            None => (None, None),
         };
+        serde_json::json!({
+            "url": url,
+            "source_url": source_url,
+            "request_type": normalize_request_type(request_type),
+            "disabled": self.is_disabled(),
+            "whitelisted": Self::is_whitelisted(url),
+            "evaluated": result.is_some(),
+            "matched": result.as_ref().map(|r| r.matched).unwrap_or(false),
+            "important": result.as_ref().map(|r| r.important).unwrap_or(false),
+            "filter": filter,
+            "exception": exception,
+            "redirect": result.as_ref().and_then(|r| r.redirect.clone()),
+            "rewritten_url": result.as_ref().and_then(|r| r.rewritten_url.clone()),
+        })
+    }
+
+    pub fn is_disabled(&self) -> bool {
+        self.disabled.load(Ordering::SeqCst)
+    }
+
+    /// Bypasses the engine until the app restarts; never persisted.
+    pub fn set_disabled(&self, disabled: bool) {
+        self.disabled.store(disabled, Ordering::SeqCst);
+        println!(
+            "🛡️ AdBlock {} for this session",
+            if disabled { "disabled" } else { "enabled" }
+        );
+    }
 
-        let normalized_type = match request_type {
-            "fetch" | "xhr" => "xmlhttprequest",
-            "link" => "stylesheet",
-            "img" => "image",
-            "video" | "audio" => "media",
-            _ => request_type,
+    /// Names the rules of the logged blocks first; like `explain_url`, this may
+    /// build the rule engine. The stats stay unlocked meanwhile so checks go on.
+    pub fn stats_json(&self) -> serde_json::Value {
+        let pending = self.stats.lock().unwrap().unexplained();
+        if !pending.is_empty() {
+            let rules: HashMap<_, _> = pending
+                .into_iter()
+                .filter_map(|(url, source_url, request_type)| {
+                    let (filter, _) = self.rule_names(&url, &source_url, &request_type);
+                    Some(((url, source_url, request_type), filter?))
+                })
+                .collect();
+            self.stats.lock().unwrap().explain(&rules);
+        }
+        self.stats.lock().unwrap().to_json()
+    }
+
+    /// Writes the serving engine to `engine.dat` if it has not been saved yet.
+    fn save_engine(&self) -> Result<(), String> {
+        let mut unsaved = self.unsaved_engine.lock().unwrap();
+        let Some(path) = unsaved.as_ref() else {
+            return Ok(());
         };
+        if let Some(engine) = self.engine.read().unwrap().as_ref() {
+            save_engine_to_cache(engine, path).map_err(|e| e.to_string())?;
+        }
+        *unsaved = None;
+        Ok(())
+    }
 
-        Request::new(url, source_url, normalized_type)
-            .map(|req| engine.check_network_request(&req).matched)
-            .unwrap_or(false)
+    fn save_stats(&self) {
+        if let Err(e) = self.stats.lock().unwrap().save() {
+            eprintln!("AdBlock stats save error: {}", e);
+        }
     }
 
     fn is_whitelisted(url: &str) -> bool {
@@ -75,6 +279,7 @@ impl AdBlockState {
         let engine_guard = self.engine.read().unwrap();
         let resources = engine_guard
             .as_ref()
+            .filter(|_| !self.is_disabled())
             .map(|e| e.url_cosmetic_resources(url))
             .unwrap_or_else(UrlSpecificResources::empty);
 
@@ -92,12 +297,52 @@ impl AdBlockState {
     }
 }
 
+fn redirect_resources() -> Vec<Resource> {
+    REDIRECT_RESOURCES
+        .iter()
+        .map(|(name, aliases, kind, content)| Resource {
+            aliases: aliases.iter().map(|a| a.to_string()).collect(),
+            ..Resource::simple(name, kind.clone(), content)
+        })
+        .collect()
+}
+
+fn normalize_request_type(request_type: &str) -> &str {
+    match request_type {
+        "fetch" | "xhr" => "xmlhttprequest",
+        "link" => "stylesheet",
+        "img" => "image",
+        "video" | "audio" => "media",
+        _ => request_type,
+    }
+}
+
 pub fn init() -> TauriPlugin<tauri::Wry> {
     Builder::new("adblock")
         .setup(|app, _| {
             let state = AdBlockState::new();
+            // Serve the bundled rules right away; they get replaced once the lists are ready
+            state.set_engine(engine_from_filters(BASELINE_FILTERS), RuleSource::Baseline);
             app.manage(state.clone());
 
+            if let Ok(data_dir) = app.path().app_data_dir() {
+                let cache_dir = data_dir.join("adblock_cache");
+                if fs::create_dir_all(&cache_dir).is_ok() {
+                    state
+                        .stats
+                        .lock()
+                        .unwrap()
+                        .attach(cache_dir.join(STATS_FILE));
+                }
+                if let Some(shutdown) = app.try_state::<ShutdownCoordinator>() {
+                    let hook_state = state.clone();
+                    shutdown.register("adblock", move || {
+                        hook_state.save_stats();
+                        hook_state.save_engine()
+                    });
+                }
+            }
+
             let app_handle = app.clone();
             tauri::async_runtime::spawn(async move {
                 if let Err(e) = setup_filters(&app_handle).await {
@@ -105,6 +350,16 @@ pub fn init() -> TauriPlugin<tauri::Wry> {
                 }
             });
 
+            let stats_state = state.clone();
+            tauri::async_runtime::spawn(async move {
+                let mut interval =
+                    tokio::time::interval(Duration::from_secs(STATS_FLUSH_INTERVAL_SECS));
+                loop {
+                    interval.tick().await;
+                    stats_state.save_stats();
+                }
+            });
+
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -113,6 +368,8 @@ pub fn init() -> TauriPlugin<tauri::Wry> {
             get_cosmetic_resources,
             check_batch_urls,
             get_hidden_class_id_selectors,
+            report_adblock_counters,
+            check_request,
         ])
         .build()
 }
@@ -121,85 +378,66 @@ async fn setup_filters(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>
     let cache_dir = app.path().app_data_dir()?.join("adblock_cache");
     fs::create_dir_all(&cache_dir)?;
     let engine_cache_path = cache_dir.join(ENGINE_CACHE_FILE);
+    let state: tauri::State<AdBlockState> = app.state();
 
-    if let Ok(engine) = load_engine_from_cache(&engine_cache_path, CACHE_DURATION_SECS) {
-        let state: tauri::State<AdBlockState> = app.state();
-        state.set_engine(engine);
+    let cached_path = engine_cache_path.clone();
+    let cached = tokio::task::spawn_blocking(move || {
+        load_engine_from_cache(&cached_path, CACHE_DURATION_SECS)
+    })
+    .await?;
+    if let Ok(engine) = cached {
+        state.set_engine(engine, RuleSource::Lists(list_cache_paths(&cache_dir)));
         return Ok(());
     }
 
-    let filters = fetch_all_filters(&cache_dir).await?;
-    let engine = Engine::from_rules(
-        filters
-            .lines()
-            .filter(|l| !l.is_empty() && !l.starts_with('!')),
-        ParseOptions::default(),
-    );
+    let filters = fetch_all_filters(app, &cache_dir).await?;
+    if filters.trim().is_empty() {
+        return Err("No filter lists available, keeping baseline rules".into());
+    }
+    let engine = tokio::task::spawn_blocking(move || engine_from_filters(&filters)).await?;
+    state.set_engine(engine, RuleSource::Lists(list_cache_paths(&cache_dir)));
 
-    if let Err(e) = save_engine_to_cache(&engine, &engine_cache_path) {
+    // A failed write is retried by the shutdown hook
+    *state.unsaved_engine.lock().unwrap() = Some(engine_cache_path);
+    let saving = state.inner().clone();
+    if let Err(e) = tokio::task::spawn_blocking(move || saving.save_engine()).await? {
         eprintln!("Cache save error: {}", e);
     }
 
-    let state: tauri::State<AdBlockState> = app.state();
-    state.set_engine(engine);
-
     Ok(())
 }
 
-async fn fetch_all_filters(cache_dir: &Path) -> Result<String, Box<dyn std::error::Error>> {
-    let mut all_content = String::new();
-    for (name, url) in FILTER_LISTS {
-        let filename = sanitize_filename(name);
-        let cache_path = cache_dir.join(&filename);
-
-        let content = if is_cache_valid(&cache_path, CACHE_DURATION_SECS) {
-            fs::read_to_string(&cache_path).unwrap_or_default()
-        } else {
-            match download_list(url, &cache_path).await {
-                Ok(text) => text,
-                Err(e) => {
-                    eprintln!("Failed {}: {}", name, e);
-                    continue;
-                }
-            }
-        };
-
-        all_content.push_str(&content);
-        all_content.push('\n');
-    }
-    Ok(all_content)
+fn rule_lines(filters: &str) -> impl Iterator<Item = &str> {
+    filters
+        .lines()
+        .filter(|l| !l.is_empty() && !l.starts_with('!'))
 }
 
-async fn download_list(url: &str, cache_path: &Path) -> Result<String, Box<dyn std::error::Error>> {
-    let response = ureq::get(url).timeout(Duration::from_secs(30)).call()?;
-    let content = response.into_string()?;
-    if content.len() > MAX_DOWNLOAD_SIZE {
-        return Err("Content too large".into());
-    }
+/// The serving engine. It keeps no rule text, which keeps it small and lets it
+/// round-trip through `engine.dat`.
+fn engine_from_filters(filters: &str) -> Engine {
+    Engine::from_rules(rule_lines(filters), ParseOptions::default())
+}
 
-    fs::write(cache_path, &content)?;
-    Ok(content)
+/// Same rules in debug mode, so every verdict names the rule that matched.
+fn rule_engine_from_filters(filters: &str) -> Engine {
+    Engine::from_rules_debug(rule_lines(filters), ParseOptions::default())
 }
 
-fn is_cache_valid(path: &Path, max_age_secs: u64) -> bool {
-    fs::metadata(path)
-        .and_then(|m| m.modified())
-        .map(|modified| {
-            let now = SystemTime::now()
-                .duration_since(UNIX_EPOCH)
-                .unwrap()
-                .as_secs();
-            let cache_time = modified.duration_since(UNIX_EPOCH).unwrap().as_secs();
-            now.saturating_sub(cache_time) < max_age_secs
-        })
-        .unwrap_or(false)
+fn list_cache_paths(cache_dir: &Path) -> Vec<PathBuf> {
+    FILTER_LISTS
+        .iter()
+        .map(|(name, _)| cache_dir.join(sanitize_filename(name)))
+        .collect()
 }
 
 fn save_engine_to_cache(
     engine: &Engine,
     cache_path: &Path,
 ) -> Result<(), Box<dyn std::error::Error>> {
-    fs::write(cache_path, engine.serialize())?;
+    let tmp_path = cache_path.with_extension("dat.tmp");
+    fs::write(&tmp_path, engine.serialize())?;
+    fs::rename(&tmp_path, cache_path)?;
     Ok(())
 }
 
@@ -219,6 +457,125 @@ fn load_engine_from_cache(
     Ok(engine)
 }
 
+async fn fetch_all_filters(
+    app: &AppHandle,
+    cache_dir: &Path,
+) -> Result<String, Box<dyn std::error::Error>> {
+    let agent = download_agent(
+        Duration::from_secs(CONNECT_TIMEOUT_SECS),
+        Duration::from_secs(READ_TIMEOUT_SECS),
+    );
+    let semaphore = Arc::new(Semaphore::new(MAX_PARALLEL_DOWNLOADS));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let total = FILTER_LISTS.len();
+    let mut tasks = JoinSet::new();
+
+    for (index, &(name, url)) in FILTER_LISTS.iter().enumerate() {
+        let cache_path = cache_dir.join(sanitize_filename(name));
+        let semaphore = semaphore.clone();
+        let completed = completed.clone();
+        let app = app.clone();
+        let agent = agent.clone();
+
+        tasks.spawn(async move {
+            let _permit = semaphore.acquire_owned().await.ok();
+            let result = load_list(&agent, url, cache_path).await;
+
+            let done = completed.fetch_add(1, Ordering::SeqCst) + 1;
+            let status = match &result {
+                Ok(_) => "ok".to_string(),
+                Err(e) => {
+                    eprintln!("Failed {}: {}", name, e);
+                    "failed".to_string()
+                }
+            };
+            let _ = app.emit(
+                PROGRESS_EVENT,
+                serde_json::json!({
+                    "name": name,
+                    "status": status,
+                    "completed": done,
+                    "total": total,
+                }),
+            );
+
+            (index, result.ok())
+        });
+    }
+
+    // Keep the original list order so the combined rules are deterministic
+    let mut contents: Vec<Option<String>> = vec![None; total];
+    while let Some(joined) = tasks.join_next().await {
+        match joined {
+            Ok((index, content)) => contents[index] = content,
+            Err(e) => eprintln!("Filter download task failed: {}", e),
+        }
+    }
+
+    let mut all_content = String::new();
+    for content in contents.into_iter().flatten() {
+        all_content.push_str(&content);
+        all_content.push('\n');
+    }
+    Ok(all_content)
+}
+
+/// HTTP agent for the filter lists; its timeouts end stalled downloads.
+fn download_agent(connect: Duration, read: Duration) -> ureq::Agent {
+    ureq::AgentBuilder::new()
+        .timeout_connect(connect)
+        .timeout_read(read)
+        .timeout(Duration::from_secs(DOWNLOAD_TIMEOUT_SECS))
+        .build()
+}
+
+async fn load_list(
+    agent: &ureq::Agent,
+    url: &'static str,
+    cache_path: PathBuf,
+) -> Result<String, String> {
+    if is_cache_valid(&cache_path, CACHE_DURATION_SECS) {
+        if let Ok(content) = fs::read_to_string(&cache_path) {
+            return Ok(content);
+        }
+    }
+
+    let agent = agent.clone();
+    let download = tokio::task::spawn_blocking(move || download_list(&agent, url));
+    // The cache is written here, so a download that outlives the backstop never lands
+    let content =
+        match tokio::time::timeout(Duration::from_secs(DOWNLOAD_BACKSTOP_SECS), download).await {
+            Ok(Ok(result)) => result?,
+            Ok(Err(e)) => return Err(format!("Download task panicked: {}", e)),
+            Err(_) => return Err("Timed out".to_string()),
+        };
+    fs::write(&cache_path, &content).map_err(|e| e.to_string())?;
+    Ok(content)
+}
+
+fn download_list(agent: &ureq::Agent, url: &str) -> Result<String, String> {
+    let response = agent.get(url).call().map_err(|e| e.to_string())?;
+    let content = response.into_string().map_err(|e| e.to_string())?;
+    if content.len() > MAX_DOWNLOAD_SIZE {
+        return Err("Content too large".to_string());
+    }
+    Ok(content)
+}
+
+fn is_cache_valid(path: &Path, max_age_secs: u64) -> bool {
+    fs::metadata(path)
+        .and_then(|m| m.modified())
+        .map(|modified| {
+            let now = SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs();
+            let cache_time = modified.duration_since(UNIX_EPOCH).unwrap().as_secs();
+            now.saturating_sub(cache_time) < max_age_secs
+        })
+        .unwrap_or(false)
+}
+
 fn sanitize_filename(name: &str) -> String {
     name.replace(&[' ', '-', '\''][..], "_")
         .replace(|c: char| !c.is_alphanumeric() && c != '_', "")
@@ -236,6 +593,16 @@ pub async fn check_batch_urls(
         .collect())
 }
 
+#[tauri::command]
+pub async fn check_request(
+    url: String,
+    source_url: String,
+    request_type: String,
+    state: tauri::State<'_, AdBlockState>,
+) -> Result<NetworkDecision, String> {
+    Ok(state.check_request(&url, &source_url, &request_type))
+}
+
 #[tauri::command]
 pub async fn is_url_blocked(
     url: String,
@@ -269,6 +636,198 @@ pub async fn get_hidden_class_id_selectors(
     let engine_guard = state.engine.read().unwrap();
     Ok(engine_guard
         .as_ref()
+        .filter(|_| !state.is_disabled())
         .map(|e| e.hidden_class_id_selectors(classes, ids, &exceptions))
         .unwrap_or_default())
 }
+
+#[tauri::command]
+pub async fn report_adblock_counters(
+    counters: HashMap<String, u64>,
+    state: tauri::State<'_, AdBlockState>,
+) -> Result<(), String> {
+    state.stats.lock().unwrap().record_page_counters(&counters);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use adblock::lists::{parse_filter, FilterSet, RuleTypes};
+
+    fn all_rules() -> ParseOptions {
+        ParseOptions {
+            rule_types: RuleTypes::All,
+            ..ParseOptions::default()
+        }
+    }
+
+    fn baseline_rules() -> impl Iterator<Item = &'static str> {
+        BASELINE_FILTERS
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.is_empty() && !l.starts_with('!'))
+    }
+
+    #[test]
+    fn baseline_filters_parse() {
+        let invalid: Vec<&str> = baseline_rules()
+            .filter(|line| parse_filter(line, true, all_rules()).is_err())
+            .collect();
+        assert!(invalid.is_empty(), "invalid baseline rules: {:?}", invalid);
+
+        let mut set = FilterSet::new(true);
+        set.add_filters(baseline_rules(), all_rules());
+        assert!(!Engine::from_filter_set(set, true).serialize().is_empty());
+    }
+
+    #[test]
+    fn baseline_blocks_known_ad_server() {
+        let engine = engine_from_filters(BASELINE_FILTERS);
+        let request = Request::new(
+            "https://ad.doubleclick.net/ddm/adj/N1234.yt/B5678;sz=1x1",
+            "https://music.youtube.com/",
+            "script",
+        )
+        .unwrap();
+        assert!(engine.check_network_request(&request).matched);
+        let named = rule_engine_from_filters(BASELINE_FILTERS).check_network_request(&request);
+        assert!(named.filter.is_some());
+
+        let request = Request::new(
+            "https://music.youtube.com/youtubei/v1/next",
+            "https://music.youtube.com/",
+            "xhr",
+        )
+        .unwrap();
+        assert!(!engine.check_network_request(&request).matched);
+    }
+
+    /// State serving `rules` as if they came from a cached list file in `dir`.
+    fn state_with_list(dir: &Path, rules: &str) -> AdBlockState {
+        fs::create_dir_all(dir).unwrap();
+        let path = dir.join(sanitize_filename("Test list"));
+        fs::write(&path, rules).unwrap();
+        let state = AdBlockState::new();
+        state.set_engine(engine_from_filters(rules), RuleSource::Lists(vec![path]));
+        state
+    }
+
+    #[test]
+    fn stats_log_names_the_matching_list_rule() {
+        let dir = std::env::temp_dir().join(format!(
+            "yt-hear-adblock-stats-log-test-{}",
+            std::process::id()
+        ));
+        let state = state_with_list(&dir, "! Title: list\n||tracker.example^$third-party\n");
+
+        assert!(state.check_url(
+            "https://tracker.example/pixel.gif",
+            "https://music.youtube.com/",
+            "img",
+        ));
+        let stats = state.stats_json();
+        assert_eq!(
+            stats["recent"][0]["filter"],
+            "||tracker.example^$third-party"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn unsaved_engine_is_written_once() {
+        let dir =
+            std::env::temp_dir().join(format!("yt-hear-adblock-save-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let state = AdBlockState::new();
+        state.set_engine(
+            engine_from_filters("||ads.example.com^\n"),
+            RuleSource::Baseline,
+        );
+        let path = dir.join(ENGINE_CACHE_FILE);
+        *state.unsaved_engine.lock().unwrap() = Some(path.clone());
+
+        // The folder is missing, so the write fails and stays pending
+        assert!(state.save_engine().is_err());
+        fs::create_dir_all(&dir).unwrap();
+        state.save_engine().unwrap();
+        assert!(load_engine_from_cache(&path, CACHE_DURATION_SECS).is_ok());
+
+        fs::remove_file(&path).unwrap();
+        state.save_engine().unwrap();
+        assert!(!path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn engine_cache_round_trips() {
+        let dir = std::env::temp_dir().join(format!(
+            "yt-hear-adblock-engine-test-{}",
+            std::process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(ENGINE_CACHE_FILE);
+        save_engine_to_cache(&engine_from_filters("||ads.example.com^\n"), &path).unwrap();
+
+        let engine = load_engine_from_cache(&path, CACHE_DURATION_SECS).unwrap();
+        let request = Request::new(
+            "https://ads.example.com/banner.js",
+            "https://music.youtube.com/",
+            "script",
+        )
+        .unwrap();
+        assert!(engine.check_network_request(&request).matched);
+        assert!(load_engine_from_cache(&path, 0).is_err());
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn explain_url_names_a_rule_from_a_cached_list() {
+        let dir = std::env::temp_dir().join(format!("yt-hear-adblock-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join(sanitize_filename("Test list"));
+        fs::write(&cache_path, "! Title: Test list\n||ads.example.com^\n").unwrap();
+
+        // A fresh cache file is read back without touching the network
+        let agent = download_agent(Duration::from_secs(1), Duration::from_secs(1));
+        let list = load_list(&agent, "https://lists.invalid/test.txt", cache_path)
+            .await
+            .unwrap();
+        let state = AdBlockState::new();
+        let source = RuleSource::Lists(vec![dir.join(sanitize_filename("Test list"))]);
+        state.set_engine(engine_from_filters(&list), source);
+        state.set_disabled(true);
+
+        let explained = state.explain_url(
+            "https://ads.example.com/banner.js",
+            "https://music.youtube.com/",
+            "script",
+        );
+        assert_eq!(explained["matched"], true);
+        assert_eq!(explained["filter"], "||ads.example.com^");
+        assert_eq!(explained["disabled"], true);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn a_stalled_list_times_out_without_writing_its_cache() {
+        // Accepted by the kernel backlog but never answered
+        let server = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let url: &'static str =
+            Box::leak(format!("http://{}/list.txt", server.local_addr().unwrap()).into_boxed_str());
+        let dir =
+            std::env::temp_dir().join(format!("yt-hear-adblock-stall-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let cache_path = dir.join(sanitize_filename("Stalled list"));
+
+        let agent = download_agent(Duration::from_secs(1), Duration::from_millis(200));
+        let started = std::time::Instant::now();
+        assert!(load_list(&agent, url, cache_path.clone()).await.is_err());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert!(!cache_path.exists());
+
+        drop(server);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,366 @@
+// src-tauri/src/adblock_stats.rs
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const MAX_RECENT_DECISIONS: usize = 200;
+const TOP_DOMAINS_LIMIT: usize = 20;
+/// Domains kept with their block counts; once twice this many are tracked the
+/// least-hit ones are dropped, leaving room for new domains to build a count.
+const MAX_TRACKED_DOMAINS: usize = 500;
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct AdBlockCounters {
+    pub requests_checked: u64,
+    pub network_blocked: u64,
+    pub cosmetic_blocked: u64,
+    pub youtube_ads_skipped: u64,
+    pub youtube_tracking_blocked: u64,
+}
+
+impl AdBlockCounters {
+    /// Applies a counter reported by `adblock.js` (camelCase names, as kept in the page).
+    fn add_page_counter(&mut self, name: &str, amount: u64) {
+        match name {
+            "cosmeticBlocked" => self.cosmetic_blocked += amount,
+            "youtubeAdsSkipped" => self.youtube_ads_skipped += amount,
+            "youtubeTrackingBlocked" => self.youtube_tracking_blocked += amount,
+            // networkBlocked is already counted by check_url on the Rust side
+            _ => {}
+        }
+    }
+}
+
+/// What `stats.json` holds: the lifetime totals and blocks per domain.
+#[derive(Default, Serialize, Deserialize)]
+#[serde(default)]
+struct StoredStats {
+    #[serde(flatten)]
+    counters: AdBlockCounters,
+    blocked_domains: HashMap<String, u64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Decision {
+    pub timestamp: DateTime<Utc>,
+    pub url: String,
+    pub source_url: String,
+    pub request_type: String,
+    pub blocked: bool,
+    pub filter: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainCount {
+    pub domain: String,
+    pub count: u64,
+}
+
+pub struct AdBlockStats {
+    session_started: DateTime<Utc>,
+    session: AdBlockCounters,
+    lifetime: AdBlockCounters,
+    blocked_domains: HashMap<String, u64>,
+    recent: VecDeque<Decision>,
+    path: Option<PathBuf>,
+    dirty: bool,
+}
+
+impl Default for AdBlockStats {
+    fn default() -> Self {
+        Self {
+            session_started: Utc::now(),
+            session: AdBlockCounters::default(),
+            lifetime: AdBlockCounters::default(),
+            blocked_domains: HashMap::new(),
+            recent: VecDeque::with_capacity(MAX_RECENT_DECISIONS),
+            path: None,
+            dirty: false,
+        }
+    }
+}
+
+impl AdBlockStats {
+    /// Points the stats at their file on disk and picks up the lifetime totals stored there.
+    pub fn attach(&mut self, path: PathBuf) {
+        if let Ok(data) = fs::read_to_string(&path) {
+            match serde_json::from_str::<StoredStats>(&data) {
+                Ok(StoredStats {
+                    counters: stored,
+                    blocked_domains,
+                }) => {
+                    self.lifetime.requests_checked += stored.requests_checked;
+                    self.lifetime.network_blocked += stored.network_blocked;
+                    self.lifetime.cosmetic_blocked += stored.cosmetic_blocked;
+                    self.lifetime.youtube_ads_skipped += stored.youtube_ads_skipped;
+                    self.lifetime.youtube_tracking_blocked += stored.youtube_tracking_blocked;
+                    for (domain, count) in blocked_domains {
+                        *self.blocked_domains.entry(domain).or_insert(0) += count;
+                    }
+                    self.prune_domains();
+                }
+                Err(e) => eprintln!("AdBlock stats parse error: {}", e),
+            }
+        }
+        self.path = Some(path);
+    }
+
+    /// Logs a verdict. The serving engine keeps no rule text, so `filter` is
+    /// filled in later through [`Self::unexplained`] and [`Self::explain`].
+    pub fn record(&mut self, url: &str, source_url: &str, request_type: &str, blocked: bool) {
+        self.session.requests_checked += 1;
+        self.lifetime.requests_checked += 1;
+
+        if blocked {
+            self.session.network_blocked += 1;
+            self.lifetime.network_blocked += 1;
+            if let Some(domain) = domain_of(url) {
+                *self.blocked_domains.entry(domain).or_insert(0) += 1;
+                self.prune_domains();
+            }
+        }
+
+        if self.recent.len() == MAX_RECENT_DECISIONS {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(Decision {
+            timestamp: Utc::now(),
+            url: url.to_string(),
+            source_url: source_url.to_string(),
+            request_type: request_type.to_string(),
+            blocked,
+            filter: None,
+        });
+
+        self.dirty = true;
+    }
+
+    fn prune_domains(&mut self) {
+        if self.blocked_domains.len() < MAX_TRACKED_DOMAINS * 2 {
+            return;
+        }
+        let mut counts: Vec<u64> = self.blocked_domains.values().copied().collect();
+        counts.sort_unstable_by(|a, b| b.cmp(a));
+        let threshold = counts[MAX_TRACKED_DOMAINS - 1];
+        // Ties at the threshold are kept, then trimmed to the cap
+        self.blocked_domains.retain(|_, count| *count >= threshold);
+        if self.blocked_domains.len() > MAX_TRACKED_DOMAINS {
+            let mut tied: Vec<String> = self
+                .blocked_domains
+                .iter()
+                .filter(|(_, count)| **count == threshold)
+                .map(|(domain, _)| domain.clone())
+                .collect();
+            tied.sort();
+            let excess = self.blocked_domains.len() - MAX_TRACKED_DOMAINS;
+            for domain in tied.into_iter().rev().take(excess) {
+                self.blocked_domains.remove(&domain);
+            }
+        }
+    }
+
+    /// Blocked requests in the recent log whose rule is not known yet, as
+    /// `(url, source_url, request_type)`.
+    pub fn unexplained(&self) -> HashSet<(String, String, String)> {
+        self.recent
+            .iter()
+            .filter(|d| d.blocked && d.filter.is_none())
+            .map(|d| (d.url.clone(), d.source_url.clone(), d.request_type.clone()))
+            .collect()
+    }
+
+    /// Names the rule of each logged request found in `rules`.
+    pub fn explain(&mut self, rules: &HashMap<(String, String, String), String>) {
+        for decision in self.recent.iter_mut().filter(|d| d.filter.is_none()) {
+            let key = (
+                decision.url.clone(),
+                decision.source_url.clone(),
+                decision.request_type.clone(),
+            );
+            decision.filter = rules.get(&key).cloned();
+        }
+    }
+
+    pub fn record_page_counters(&mut self, counters: &HashMap<String, u64>) {
+        for (name, amount) in counters {
+            self.session.add_page_counter(name, *amount);
+            self.lifetime.add_page_counter(name, *amount);
+        }
+        self.dirty = true;
+    }
+
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut domains: Vec<DomainCount> = self
+            .blocked_domains
+            .iter()
+            .map(|(domain, count)| DomainCount {
+                domain: domain.clone(),
+                count: *count,
+            })
+            .collect();
+        domains.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.domain.cmp(&b.domain)));
+        domains.truncate(TOP_DOMAINS_LIMIT);
+
+        serde_json::json!({
+            "session_started": self.session_started,
+            "session": self.session,
+            "lifetime": self.lifetime,
+            "top_blocked_domains": domains,
+            "recent": self.recent,
+        })
+    }
+
+    /// Writes the lifetime totals if anything changed since the last save.
+    pub fn save(&mut self) -> Result<(), Box<dyn std::error::Error>> {
+        if !self.dirty {
+            return Ok(());
+        }
+        let Some(path) = self.path.as_ref() else {
+            return Ok(());
+        };
+        write_stats(path, &self.lifetime, &self.blocked_domains)?;
+        self.dirty = false;
+        Ok(())
+    }
+}
+
+fn write_stats(
+    path: &Path,
+    counters: &AdBlockCounters,
+    blocked_domains: &HashMap<String, u64>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let stored = StoredStats {
+        counters: counters.clone(),
+        blocked_domains: blocked_domains.clone(),
+    };
+    let tmp_path = path.with_extension("json.tmp");
+    fs::write(&tmp_path, serde_json::to_vec_pretty(&stored)?)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+fn domain_of(url: &str) -> Option<String> {
+    url::Url::parse(url).ok().and_then(|u| {
+        u.host_str()
+            .map(|h| h.trim_start_matches("www.").to_string())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn recent_decisions_keep_only_the_newest() {
+        let mut stats = AdBlockStats::default();
+        for i in 0..MAX_RECENT_DECISIONS + 5 {
+            let url = format!("https://cdn.example.com/{}.js", i);
+            stats.record(&url, "https://music.youtube.com/", "script", false);
+        }
+
+        assert_eq!(stats.recent.len(), MAX_RECENT_DECISIONS);
+        assert_eq!(
+            stats.recent.front().unwrap().url,
+            "https://cdn.example.com/5.js"
+        );
+        let last = format!("https://cdn.example.com/{}.js", MAX_RECENT_DECISIONS + 4);
+        assert_eq!(stats.recent.back().unwrap().url, last);
+        assert_eq!(
+            stats.session.requests_checked,
+            MAX_RECENT_DECISIONS as u64 + 5
+        );
+    }
+
+    #[test]
+    fn top_domains_count_blocked_requests_only() {
+        let mut stats = AdBlockStats::default();
+        let source = "https://music.youtube.com/";
+        for _ in 0..3 {
+            stats.record("https://www.ads.example/a.js", source, "script", true);
+        }
+        stats.record("https://tracker.example/p", source, "image", true);
+        stats.record("https://allowed.example/x", source, "script", false);
+
+        let pending = stats.unexplained();
+        assert_eq!(pending.len(), 2);
+        let rules = pending
+            .into_iter()
+            .filter(|(url, _, _)| url.contains("ads.example"))
+            .map(|key| (key, "||ads.example^".to_string()))
+            .collect();
+        stats.explain(&rules);
+
+        let json = stats.to_json();
+        let top = json["top_blocked_domains"].as_array().unwrap();
+        assert_eq!(top.len(), 2);
+        assert_eq!(top[0]["domain"], "ads.example");
+        assert_eq!(top[0]["count"], 3);
+        assert_eq!(top[1]["domain"], "tracker.example");
+        assert_eq!(json["session"]["network_blocked"], 4);
+        assert_eq!(json["recent"][0]["filter"], "||ads.example^");
+    }
+
+    #[test]
+    fn top_domains_are_capped() {
+        let mut stats = AdBlockStats::default();
+        for i in 0..TOP_DOMAINS_LIMIT + 3 {
+            let url = format!("https://ads{}.example/a.js", i);
+            stats.record(&url, "https://music.youtube.com/", "script", true);
+        }
+        let json = stats.to_json();
+        assert_eq!(
+            json["top_blocked_domains"].as_array().unwrap().len(),
+            TOP_DOMAINS_LIMIT
+        );
+    }
+
+    #[test]
+    fn tracked_domains_are_capped_by_hits() {
+        let mut stats = AdBlockStats::default();
+        let source = "https://music.youtube.com/";
+        for _ in 0..2 {
+            stats.record("https://ads.example/a.js", source, "script", true);
+        }
+        for i in 0..MAX_TRACKED_DOMAINS * 2 {
+            let url = format!("https://ads{}.example/a.js", i);
+            stats.record(&url, source, "script", true);
+        }
+        assert!(stats.blocked_domains.len() < MAX_TRACKED_DOMAINS * 2);
+        assert_eq!(stats.blocked_domains.get("ads.example"), Some(&2));
+    }
+
+    #[test]
+    fn blocked_domains_survive_a_restart() {
+        let path = std::env::temp_dir().join(format!(
+            "yt-hear-adblock-stats-test-{}.json",
+            std::process::id()
+        ));
+        let _ = fs::remove_file(&path);
+        let mut stats = AdBlockStats::default();
+        stats.attach(path.clone());
+        stats.record(
+            "https://ads.example/a.js",
+            "https://music.youtube.com/",
+            "script",
+            true,
+        );
+        stats.save().unwrap();
+
+        let mut restarted = AdBlockStats::default();
+        restarted.attach(path.clone());
+        let json = restarted.to_json();
+        assert_eq!(json["top_blocked_domains"][0]["domain"], "ads.example");
+        assert_eq!(json["lifetime"]["network_blocked"], 1);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn counters_only_files_still_load() {
+        let stored: StoredStats = serde_json::from_str(r#"{"network_blocked": 7}"#).unwrap();
+        assert_eq!(stored.counters.network_blocked, 7);
+        assert!(stored.blocked_domains.is_empty());
+    }
+}
@@ -1,12 +1,126 @@
 use serde_json::Value;
 use std::collections::HashMap;
 use std::sync::Arc;
-use std::time::Duration;
+use std::time::{Duration, Instant};
 use tauri::{AppHandle, Emitter, Runtime};
-use tokio::sync::oneshot;
 use tokio::sync::Mutex; // Cambiar de std::sync::Mutex
+use tokio::sync::{broadcast, oneshot};
+use tokio::task::JoinHandle;
 use uuid::Uuid;
 
+/// Topics de telemetría que envían los scripts de la página (eventos `ytm:<topic>`)
+/// y la forma de cada payload. Los consumidores en Rust leen estos tipos.
+pub mod topics {
+    use serde::de::DeserializeOwned;
+    use serde::{Deserialize, Serialize};
+    use serde_json::Value;
+
+    /// Resultado de `getCurrentSong` (songinfo.js); lo envía observer.js al cambiar canción o estado.
+    pub const SONG_INFO: &str = "song-info";
+    /// `{ currentTime, duration }` en segundos; playerListeners.js, en cada `timeupdate` del video.
+    pub const TIME_UPDATE: &str = "time-update";
+    /// `{ volume (0-100), isMuted }`; playerListeners.js.
+    pub const VOLUME_CHANGE: &str = "volume-change";
+    /// `{ shuffle, fullscreen, repeat }`; playerListeners.js.
+    pub const STATE_CHANGE: &str = "state-change";
+
+    /// Nombre del evento de Tauri que emite `push_telemetry` para `topic`.
+    pub fn event(topic: &str) -> String {
+        format!("ytm:{}", topic)
+    }
+
+    /// Payload de `song-info`; `null` cuando no hay video.
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default, rename_all = "camelCase")]
+    pub struct SongInfo {
+        #[serde(rename = "type")]
+        pub kind: String,
+        pub title: String,
+        pub artist: String,
+        pub album: String,
+        pub image_src: String,
+        pub is_paused: bool,
+        /// Segundos enteros (la página los redondea hacia abajo).
+        pub current_time: u64,
+        pub duration: u64,
+        pub url: String,
+    }
+
+    /// Payload de `time-update`.
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default, rename_all = "camelCase")]
+    pub struct TimeUpdate {
+        pub current_time: f64,
+        pub duration: f64,
+    }
+
+    /// Payload de `volume-change`.
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default, rename_all = "camelCase")]
+    pub struct VolumeChange {
+        /// 0-100, entero.
+        pub volume: u64,
+        pub is_muted: bool,
+    }
+
+    /// Payload de `state-change`; `repeat` es `NONE`, `ONE` o `ALL`.
+    #[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+    #[serde(default, rename_all = "camelCase")]
+    pub struct StateChange {
+        pub shuffle: bool,
+        pub fullscreen: bool,
+        pub repeat: String,
+    }
+
+    /// Payload tipado de un evento; `None` si es `null` o no tiene esa forma.
+    pub fn parse<T: DeserializeOwned>(payload: &Value) -> Option<T> {
+        if payload.is_null() {
+            return None;
+        }
+        T::deserialize(payload).ok()
+    }
+
+    /// Un payload de ejemplo por topic, tal como lo envía la página; los tests
+    /// de los consumidores parten de aquí.
+    #[cfg(test)]
+    pub fn sample(topic: &str) -> Value {
+        use serde_json::json;
+        match topic {
+            SONG_INFO => json!({
+                "type": "current",
+                "title": "Song",
+                "artist": "Artist",
+                "album": "Album",
+                "imageSrc": "https://lh3.googleusercontent.com/cover",
+                "isPaused": false,
+                "currentTime": 12,
+                "duration": 215,
+                "url": "https://music.youtube.com/watch?v=abc",
+            }),
+            TIME_UPDATE => json!({ "currentTime": 12.5, "duration": 215.3 }),
+            VOLUME_CHANGE => json!({ "volume": 35, "isMuted": false }),
+            STATE_CHANGE => json!({ "shuffle": true, "fullscreen": false, "repeat": "ALL" }),
+            _ => panic!("{} is not sent by the page", topic),
+        }
+    }
+
+    /// `sample(topic)` con algunos campos cambiados.
+    #[cfg(test)]
+    pub fn sample_with(topic: &str, changes: Value) -> Value {
+        let mut payload = sample(topic);
+        for (key, value) in changes.as_object().unwrap() {
+            assert!(
+                payload.get(key).is_some(),
+                "the page sends no {} in {}",
+                key,
+                topic
+            );
+            payload[key] = value.clone();
+        }
+        payload
+    }
+}
+
 #[derive(serde::Serialize, Clone)]
 struct RequestPayload {
     request_id: String,
@@ -14,16 +128,24 @@ struct RequestPayload {
 }
 pub struct AppState {
     pub http_server_shutdown: Arc<Mutex<Option<oneshot::Sender<()>>>>,
+    pub http_server_task: Arc<Mutex<Option<JoinHandle<()>>>>,
     pub pending_requests: Arc<Mutex<HashMap<String, oneshot::Sender<Value>>>>,
     pub app_handle: Arc<Mutex<Option<AppHandle>>>,
+    /// Último largo de cola visto y cuándo; lo usa la validación de índices de la API.
+    pub queue_len_cache: Arc<Mutex<Option<(Instant, usize)>>>,
+    /// Copia de cada evento de telemetría `(topic, payload)` para consumidores en Rust.
+    pub telemetry: broadcast::Sender<(String, Value)>,
 }
 
 impl Clone for AppState {
     fn clone(&self) -> Self {
         Self {
             http_server_shutdown: Arc::clone(&self.http_server_shutdown),
+            http_server_task: Arc::clone(&self.http_server_task),
             pending_requests: Arc::clone(&self.pending_requests),
             app_handle: Arc::clone(&self.app_handle),
+            queue_len_cache: Arc::clone(&self.queue_len_cache),
+            telemetry: self.telemetry.clone(),
         }
     }
 }
@@ -32,8 +154,11 @@ impl Default for AppState {
     fn default() -> Self {
         Self {
             http_server_shutdown: Arc::new(Mutex::new(None)),
+            http_server_task: Arc::new(Mutex::new(None)),
             pending_requests: Arc::new(Mutex::new(HashMap::new())),
             app_handle: Arc::new(Mutex::new(None)),
+            queue_len_cache: Arc::new(Mutex::new(None)),
+            telemetry: broadcast::channel(64).0,
         }
     }
 }
@@ -75,14 +200,16 @@ impl AppState {
         }
     }
 
-    pub async fn emit_to_frontend(&self, event: &str, payload: Value) {
+    pub async fn emit_to_frontend(&self, event: &str, payload: Value) -> Result<(), String> {
         // Hacer async
         let handle_guard = self.app_handle.lock().await; // Cambiar a .await
-        if let Some(handle) = handle_guard.as_ref() {
-            if let Err(e) = handle.emit(event, payload) {
-                eprintln!("❌ Error emit_to_frontend {}: {}", event, e);
-            }
-        }
+        let Some(handle) = handle_guard.as_ref() else {
+            return Err("AppHandle no inicializado".to_string());
+        };
+        handle.emit(event, payload).map_err(|e| {
+            eprintln!("❌ Error emit_to_frontend {}: {}", event, e);
+            e.to_string()
+        })
     }
 }
 
@@ -108,10 +235,44 @@ pub async fn resolve_request(
 #[tauri::command]
 pub fn push_telemetry<R: Runtime>(
     app: AppHandle<R>,
-    _state: tauri::State<'_, Arc<AppState>>,
+    state: tauri::State<'_, Arc<AppState>>,
     topic: String,
     payload: Value,
 ) {
-    let event_name = format!("ytm:{}", topic);
-    let _ = app.emit(&event_name, payload);
+    let _ = app.emit(&topics::event(&topic), &payload);
+    // Sin suscriptores `send` falla; no es un error
+    let _ = state.telemetry.send((topic, payload));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::topics::*;
+    use serde::de::DeserializeOwned;
+    use serde::Serialize;
+    use serde_json::Value;
+
+    /// Every field of the sample is modelled: it survives a round trip.
+    fn round_trips<T: DeserializeOwned + Serialize>(topic: &str) {
+        let sample = sample(topic);
+        let parsed: T = parse(&sample).unwrap_or_else(|| panic!("{} does not parse", topic));
+        assert_eq!(serde_json::to_value(parsed).unwrap(), sample, "{}", topic);
+    }
+
+    #[test]
+    fn samples_match_the_payload_types() {
+        round_trips::<SongInfo>(SONG_INFO);
+        round_trips::<TimeUpdate>(TIME_UPDATE);
+        round_trips::<VolumeChange>(VOLUME_CHANGE);
+        round_trips::<StateChange>(STATE_CHANGE);
+    }
+
+    #[test]
+    fn null_or_malformed_payloads_do_not_parse() {
+        assert_eq!(parse::<SongInfo>(&Value::Null), None);
+        let time = sample_with(TIME_UPDATE, serde_json::json!({ "currentTime": "soon" }));
+        assert_eq!(parse::<TimeUpdate>(&time), None);
+        // Missing fields take their defaults
+        let song: SongInfo = parse(&serde_json::json!({ "title": "Song" })).unwrap();
+        assert_eq!((song.title.as_str(), song.duration), ("Song", 0));
+    }
 }
@@ -0,0 +1,577 @@
+// src-tauri/src/cli.rs
+
+//! `yt-hear ctl <command>`: drives a running instance through the local REST API.
+//!
+//! Exit codes: 0 success, 1 the API reported an error, 2 bad usage,
+//! 3 no running API server could be reached.
+
+use serde_json::{json, Value};
+use std::fmt;
+use std::time::Duration;
+
+const EXIT_OK: i32 = 0;
+const EXIT_API_ERROR: i32 = 1;
+const EXIT_USAGE: i32 = 2;
+const EXIT_UNREACHABLE: i32 = 3;
+const REQUEST_TIMEOUT_SECS: u64 = 5;
+
+const USAGE: &str = "\
+Usage: yt-hear ctl [--port <port>] <command>
+
+Commands:
+  play | pause | toggle | next | previous
+  like | dislike | mute | shuffle | repeat
+  seek <seconds>          Jump to a position in the current song
+  volume [<0-100>]        Show or set the volume
+  queue                   Show the queue
+  queue add <videoId> [--next]
+  queue clear
+  now [--json]            Show the current song
+
+The port is taken from --port, $YT_HEAR_PORT or the running server, which
+may also be reached over its Unix socket.";
+
+enum Method {
+    Get,
+    Post,
+}
+
+/// Where the running API server listens.
+enum Endpoint {
+    Tcp(u16),
+    #[cfg(unix)]
+    Unix(std::path::PathBuf),
+}
+
+impl fmt::Display for Endpoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Endpoint::Tcp(port) => write!(f, "port {}", port),
+            #[cfg(unix)]
+            Endpoint::Unix(path) => write!(f, "socket {}", path.display()),
+        }
+    }
+}
+
+struct Call {
+    method: Method,
+    path: &'static str,
+    body: Option<Value>,
+    /// Print the response as a song line instead of raw JSON.
+    song_line: bool,
+}
+
+impl Call {
+    fn get(path: &'static str) -> Self {
+        Self {
+            method: Method::Get,
+            path,
+            body: None,
+            song_line: false,
+        }
+    }
+
+    fn post(path: &'static str, body: Option<Value>) -> Self {
+        Self {
+            method: Method::Post,
+            path,
+            body,
+            song_line: false,
+        }
+    }
+}
+
+/// Entry point for `yt-hear ctl ...`; `args` excludes the `ctl` itself.
+pub fn run(args: &[String]) -> i32 {
+    let mut args: Vec<&str> = args.iter().map(String::as_str).collect();
+    let port_flag = match take_option(&mut args, "--port") {
+        Ok(port) => port,
+        Err(e) => return usage_error(&e),
+    };
+
+    let call = match parse_command(&args) {
+        Ok(call) => call,
+        Err(e) => return usage_error(&e),
+    };
+
+    let endpoint = match resolve_endpoint(port_flag) {
+        Ok(endpoint) => endpoint,
+        Err(e) => {
+            eprintln!("❌ {}", e);
+            return EXIT_UNREACHABLE;
+        }
+    };
+
+    execute(&endpoint, call)
+}
+
+fn usage_error(message: &str) -> i32 {
+    if !message.is_empty() {
+        eprintln!("❌ {}\n", message);
+    }
+    eprintln!("{}", USAGE);
+    EXIT_USAGE
+}
+
+/// Removes `--name <value>` (or `--name=<value>`) from `args`.
+fn take_option<'a>(args: &mut Vec<&'a str>, name: &str) -> Result<Option<&'a str>, String> {
+    let Some(pos) = args.iter().position(|a| {
+        a.strip_prefix(name)
+            .is_some_and(|rest| rest.is_empty() || rest.starts_with('='))
+    }) else {
+        return Ok(None);
+    };
+    let arg = args.remove(pos);
+    match &arg[name.len()..] {
+        "" if pos < args.len() => Ok(Some(args.remove(pos))),
+        "" => Err(format!("{} needs a value", name)),
+        rest => Ok(Some(&rest[1..])),
+    }
+}
+
+fn parse_number(value: Option<&&str>, what: &str) -> Result<f64, String> {
+    let value = value.ok_or_else(|| format!("Missing {}", what))?;
+    value
+        .parse::<f64>()
+        .ok()
+        .filter(|n| n.is_finite())
+        .ok_or_else(|| format!("Invalid {}: {}", what, value))
+}
+
+fn parse_command(args: &[&str]) -> Result<Call, String> {
+    let Some((command, rest)) = args.split_first() else {
+        return Err(String::new());
+    };
+
+    let call = match (*command, rest) {
+        ("play", []) => Call::post("/play", None),
+        ("pause", []) => Call::post("/pause", None),
+        ("toggle", []) => Call::post("/toggle-play", None),
+        ("next", []) => Call::post("/next", None),
+        ("previous", []) => Call::post("/previous", None),
+        ("like", []) => Call::post("/like", None),
+        ("dislike", []) => Call::post("/dislike", None),
+        ("mute", []) => Call::post("/toggle-mute", None),
+        ("shuffle", []) => Call::post("/shuffle", None),
+        ("repeat", []) => Call::post("/repeat", None),
+        ("seek", [_]) => {
+            let seconds = parse_number(rest.first(), "seconds")?;
+            Call::post("/seek-to", Some(json!({ "seconds": seconds })))
+        }
+        ("volume", []) => Call::get("/volume"),
+        ("volume", [_]) => {
+            let volume = parse_number(rest.first(), "volume")?;
+            if !(0.0..=100.0).contains(&volume) {
+                return Err("Volume must be between 0 and 100".to_string());
+            }
+            Call::post("/volume", Some(json!({ "volume": volume })))
+        }
+        ("queue", []) => Call::get("/queue"),
+        ("queue", ["clear"]) => Call::post("/clear-queue", None),
+        ("queue", ["add", video_id]) | ("queue", ["add", video_id, "--next"]) => {
+            let position = if rest.len() == 3 {
+                "INSERT_AFTER_CURRENT_VIDEO"
+            } else {
+                "INSERT_AT_END"
+            };
+            Call::post(
+                "/queue",
+                Some(json!({ "videoId": video_id, "insertPosition": position })),
+            )
+        }
+        ("now", []) => Call {
+            song_line: true,
+            ..Call::get("/song")
+        },
+        ("now", ["--json"]) => Call::get("/song"),
+        _ => return Err(format!("Unknown command: {}", args.join(" "))),
+    };
+    Ok(call)
+}
+
+fn resolve_endpoint(flag: Option<&str>) -> Result<Endpoint, String> {
+    if let Some(port) = flag {
+        return port
+            .parse()
+            .map(Endpoint::Tcp)
+            .map_err(|_| format!("Invalid port: {}", port));
+    }
+    if let Ok(port) = std::env::var("YT_HEAR_PORT") {
+        return port
+            .parse()
+            .map(Endpoint::Tcp)
+            .map_err(|_| format!("Invalid YT_HEAR_PORT: {}", port));
+    }
+
+    let path = crate::http_server::discovery_path();
+    let raw = std::fs::read_to_string(&path)
+        .map_err(|_| "yt-hear API server is not running (start it from the tray)".to_string())?;
+    let discovery: Value = serde_json::from_str(&raw)
+        .map_err(|_| format!("Invalid discovery file: {}", path.display()))?;
+    if let Some(port) = discovery["port"]
+        .as_u64()
+        .and_then(|p| u16::try_from(p).ok())
+    {
+        return Ok(Endpoint::Tcp(port));
+    }
+    match discovery["socket"].as_str() {
+        #[cfg(unix)]
+        Some(socket) => Ok(Endpoint::Unix(socket.into())),
+        _ => Err(format!("Invalid discovery file: {}", path.display())),
+    }
+}
+
+fn execute(endpoint: &Endpoint, call: Call) -> i32 {
+    let result = match endpoint {
+        Endpoint::Tcp(port) => send_tcp(*port, &call),
+        #[cfg(unix)]
+        Endpoint::Unix(path) => send_unix(path, &call),
+    };
+    let (status, body) = match result {
+        Ok(response) => response,
+        Err(e) => {
+            eprintln!("❌ Could not reach yt-hear on {}: {}", endpoint, e);
+            return EXIT_UNREACHABLE;
+        }
+    };
+
+    if !(200..300).contains(&status) {
+        let message = serde_json::from_str::<Value>(&body)
+            .ok()
+            .and_then(|v| v["message"].as_str().map(str::to_string))
+            .unwrap_or(body);
+        eprintln!("❌ API error {}: {}", status, message);
+        return EXIT_API_ERROR;
+    }
+
+    let body: Value = serde_json::from_str(&body).unwrap_or(Value::Null);
+    // The player answers live-data requests with `{ error }` when it fails
+    if let Some(error) = body.get("error").and_then(Value::as_str) {
+        eprintln!("❌ Player error: {}", error);
+        return EXIT_API_ERROR;
+    }
+    if call.song_line {
+        println!("{}", song_line(&body));
+    } else {
+        println!(
+            "{}",
+            serde_json::to_string_pretty(&body).unwrap_or_default()
+        );
+    }
+    EXIT_OK
+}
+
+/// Sends the call over TCP; returns the status code and the raw body.
+fn send_tcp(port: u16, call: &Call) -> Result<(u16, String), String> {
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .build();
+    let url = format!("http://127.0.0.1:{}/api/v1{}", port, call.path);
+
+    let result = match (&call.method, &call.body) {
+        (Method::Get, _) => agent.get(&url).call(),
+        (Method::Post, Some(body)) => agent.post(&url).send_json(body.clone()),
+        (Method::Post, None) => agent.post(&url).call(),
+    };
+
+    match result {
+        Ok(response) => {
+            let status = response.status();
+            let body = response.into_string().map_err(|e| e.to_string())?;
+            Ok((status, body))
+        }
+        Err(ureq::Error::Status(code, response)) => {
+            Ok((code, response.into_string().unwrap_or_default()))
+        }
+        Err(e) => Err(e.to_string()),
+    }
+}
+
+/// Sends the call as a plain HTTP/1.1 request over the Unix socket (ureq only speaks TCP).
+#[cfg(unix)]
+fn send_unix(path: &std::path::Path, call: &Call) -> Result<(u16, String), String> {
+    use std::io::{Read, Write};
+
+    let mut stream = std::os::unix::net::UnixStream::connect(path).map_err(|e| e.to_string())?;
+    let timeout = Some(Duration::from_secs(REQUEST_TIMEOUT_SECS));
+    stream
+        .set_read_timeout(timeout)
+        .map_err(|e| e.to_string())?;
+    stream
+        .set_write_timeout(timeout)
+        .map_err(|e| e.to_string())?;
+
+    let method = match call.method {
+        Method::Get => "GET",
+        Method::Post => "POST",
+    };
+    let body = call.body.as_ref().map(Value::to_string).unwrap_or_default();
+    write!(
+        stream,
+        "{} /api/v1{} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\n\
+         Content-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+        method,
+        call.path,
+        body.len(),
+        body
+    )
+    .map_err(|e| e.to_string())?;
+
+    // `Connection: close` makes the server end the stream after the response
+    let mut raw = Vec::new();
+    stream.read_to_end(&mut raw).map_err(|e| e.to_string())?;
+    parse_response(&raw)
+}
+
+/// Splits a raw HTTP/1.1 response into its status code and body. The body is
+/// decoded as bytes and only then as UTF-8, so chunk sizes count bytes.
+#[cfg(unix)]
+fn parse_response(raw: &[u8]) -> Result<(u16, String), String> {
+    let split = find(raw, b"\r\n\r\n").ok_or("Incomplete HTTP response")?;
+    let head = String::from_utf8_lossy(&raw[..split]);
+    let body = &raw[split + 4..];
+    let mut lines = head.split("\r\n");
+    let status = lines
+        .next()
+        .and_then(|line| line.split(' ').nth(1))
+        .and_then(|code| code.parse().ok())
+        .ok_or("Invalid HTTP status line")?;
+    let chunked = lines.any(|line| {
+        line.split_once(':').is_some_and(|(name, value)| {
+            name.trim().eq_ignore_ascii_case("transfer-encoding")
+                && value.trim().eq_ignore_ascii_case("chunked")
+        })
+    });
+    let body = if chunked {
+        decode_chunked(body)?
+    } else {
+        body.to_vec()
+    };
+    Ok((status, String::from_utf8_lossy(&body).into_owned()))
+}
+
+#[cfg(unix)]
+fn decode_chunked(mut body: &[u8]) -> Result<Vec<u8>, String> {
+    let mut decoded = Vec::new();
+    loop {
+        let line_end = find(body, b"\r\n").ok_or("Truncated chunked body")?;
+        let size = std::str::from_utf8(&body[..line_end]).map_err(|_| "Invalid chunk size")?;
+        let size = size.split(';').next().unwrap_or_default().trim();
+        let size = usize::from_str_radix(size, 16).map_err(|_| "Invalid chunk size")?;
+        if size == 0 {
+            return Ok(decoded);
+        }
+        let rest = &body[line_end + 2..];
+        let chunk = rest.get(..size).ok_or("Truncated chunked body")?;
+        decoded.extend_from_slice(chunk);
+        body = rest[size..].strip_prefix(b"\r\n").unwrap_or(&rest[size..]);
+    }
+}
+
+#[cfg(unix)]
+fn find(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    haystack
+        .windows(needle.len())
+        .position(|window| window == needle)
+}
+
+fn song_line(song: &Value) -> String {
+    if song.is_null() {
+        return "Nothing playing".to_string();
+    }
+    let title = song["title"].as_str().unwrap_or_default();
+    let artist = song["artist"].as_str().unwrap_or_default();
+    let state = if song["isPaused"].as_bool().unwrap_or(false) {
+        "⏸"
+    } else {
+        "▶"
+    };
+    format!(
+        "{} {} - {} [{}/{}]",
+        state,
+        artist,
+        title,
+        format_time(song["currentTime"].as_u64().unwrap_or(0)),
+        format_time(song["duration"].as_u64().unwrap_or(0)),
+    )
+}
+
+fn format_time(seconds: u64) -> String {
+    format!("{}:{:02}", seconds / 60, seconds % 60)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(unix)]
+    use std::io::{BufRead, BufReader, Read, Write};
+    #[cfg(unix)]
+    use std::os::unix::net::UnixListener;
+
+    fn parse(args: &[&str]) -> Result<Call, String> {
+        parse_command(args)
+    }
+
+    fn post_body(args: &[&str]) -> (&'static str, Option<Value>) {
+        let call = parse(args).unwrap();
+        assert!(matches!(call.method, Method::Post), "{:?}", args);
+        (call.path, call.body)
+    }
+
+    #[test]
+    fn simple_commands_map_to_routes() {
+        for (command, path) in [
+            ("play", "/play"),
+            ("toggle", "/toggle-play"),
+            ("mute", "/toggle-mute"),
+            ("repeat", "/repeat"),
+        ] {
+            assert_eq!(post_body(&[command]), (path, None));
+        }
+        let volume = parse(&["volume"]).unwrap();
+        assert!(matches!(volume.method, Method::Get));
+        assert_eq!(volume.path, "/volume");
+    }
+
+    #[test]
+    fn seek_and_volume_take_numbers() {
+        assert_eq!(
+            post_body(&["seek", "90.5"]),
+            ("/seek-to", Some(json!({ "seconds": 90.5 })))
+        );
+        assert_eq!(
+            post_body(&["volume", "40"]),
+            ("/volume", Some(json!({ "volume": 40.0 })))
+        );
+        assert!(parse(&["seek", "soon"]).is_err());
+        assert!(parse(&["seek", "NaN"]).is_err());
+        assert!(parse(&["volume", "101"]).is_err());
+        assert!(parse(&["volume", "-1"]).is_err());
+    }
+
+    #[test]
+    fn queue_add_picks_the_insert_position() {
+        assert_eq!(
+            post_body(&["queue", "add", "dQw4w9WgXcQ"]),
+            (
+                "/queue",
+                Some(json!({ "videoId": "dQw4w9WgXcQ", "insertPosition": "INSERT_AT_END" }))
+            )
+        );
+        let (_, body) = post_body(&["queue", "add", "dQw4w9WgXcQ", "--next"]);
+        assert_eq!(
+            body.unwrap()["insertPosition"],
+            "INSERT_AFTER_CURRENT_VIDEO"
+        );
+        assert_eq!(post_body(&["queue", "clear"]), ("/clear-queue", None));
+    }
+
+    #[test]
+    fn now_prints_a_song_line_unless_json() {
+        assert!(parse(&["now"]).unwrap().song_line);
+        assert!(!parse(&["now", "--json"]).unwrap().song_line);
+    }
+
+    #[test]
+    fn unknown_or_malformed_commands_are_rejected() {
+        assert_eq!(parse(&[]).err(), Some(String::new()));
+        assert!(parse(&["stop"]).is_err());
+        assert!(parse(&["play", "now"]).is_err());
+        assert!(parse(&["seek"]).is_err());
+        assert!(parse(&["queue", "add"]).is_err());
+    }
+
+    #[test]
+    fn port_option_matches_exactly() {
+        let mut args = vec!["--port", "9000", "next"];
+        assert_eq!(take_option(&mut args, "--port"), Ok(Some("9000")));
+        assert_eq!(args, ["next"]);
+
+        let mut args = vec!["next", "--port=9000"];
+        assert_eq!(take_option(&mut args, "--port"), Ok(Some("9000")));
+        assert_eq!(args, ["next"]);
+
+        let mut args = vec!["--portx", "9000", "next"];
+        assert_eq!(take_option(&mut args, "--port"), Ok(None));
+        assert_eq!(args, ["--portx", "9000", "next"]);
+
+        let mut args = vec!["next", "--port"];
+        assert!(take_option(&mut args, "--port").is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parses_content_length_response() {
+        let raw = b"HTTP/1.1 200 OK\r\ncontent-type: application/json\r\ncontent-length: 13\r\n\r\n{\"volume\":40}";
+        assert_eq!(
+            parse_response(raw),
+            Ok((200, "{\"volume\":40}".to_string()))
+        );
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn parses_chunked_response() {
+        let raw = b"HTTP/1.1 404 Not Found\r\nTransfer-Encoding: chunked\r\n\r\n5\r\n{\"a\":\r\n2\r\n1}\r\n0\r\n\r\n";
+        assert_eq!(parse_response(raw), Ok((404, "{\"a\":1}".to_string())));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn chunk_boundary_inside_a_multibyte_character() {
+        // "Beyoncé" with the two bytes of "é" split across chunks
+        let mut raw = b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\n\r\n7\r\nBeyonc\xc3\r\n1\r\n\xa9\r\n0\r\n\r\n".to_vec();
+        assert_eq!(parse_response(&raw), Ok((200, "Beyoncé".to_string())));
+
+        raw.truncate(raw.len() - 12);
+        assert!(parse_response(&raw).is_err());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn talks_http_over_the_unix_socket() {
+        let dir = std::env::temp_dir().join(format!("yt-hear-cli-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("api.sock");
+        let _ = std::fs::remove_file(&path);
+        let listener = UnixListener::bind(&path).unwrap();
+
+        let server = std::thread::spawn(move || {
+            let (stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(&stream);
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+            let mut length = 0;
+            loop {
+                let mut header = String::new();
+                reader.read_line(&mut header).unwrap();
+                if header == "\r\n" {
+                    break;
+                }
+                if let Some(value) = header.to_ascii_lowercase().strip_prefix("content-length:") {
+                    length = value.trim().parse().unwrap();
+                }
+            }
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+            let reply = r#"{"ok":true}"#;
+            write!(
+                &stream,
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}",
+                reply.len(),
+                reply
+            )
+            .unwrap();
+            (request_line, String::from_utf8(body).unwrap())
+        });
+
+        let call = Call::post("/volume", Some(json!({ "volume": 40.0 })));
+        let response = send_unix(&path, &call);
+        let (request_line, body) = server.join().unwrap();
+        let _ = std::fs::remove_dir_all(&dir);
+
+        assert_eq!(response, Ok((200, r#"{"ok":true}"#.to_string())));
+        assert_eq!(request_line, "POST /api/v1/volume HTTP/1.1\r\n");
+        assert_eq!(body, r#"{"volume":40.0}"#);
+    }
+}
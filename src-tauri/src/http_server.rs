@@ -1,55 +1,285 @@
+use crate::adblock_plugin::AdBlockState;
 use crate::bridge::AppState;
+use crate::lyrics::{LyricLine, Lyrics, LyricsStore, TrackQuery};
+use crate::scripts::health::ScriptHealth;
+use crate::scripts::plugins::PluginRegistry;
+use crate::scripts::settings::ScriptSettings;
+use crate::scripts::ScriptId;
 use axum::{
-    extract::{Path, State},
+    extract::State,
     http::StatusCode,
-    routing::{delete, get, patch, post},
+    routing::{delete, get, patch, post, MethodRouter},
     Json, Router,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
 use std::net::SocketAddr;
 use std::sync::Arc;
-use tokio::sync::oneshot;
+use tauri::Manager;
+use tokio::sync::{oneshot, watch};
 use tower_http::cors::CorsLayer;
+use utoipa::{IntoResponses, OpenApi, ToSchema};
+
+mod error;
+mod middleware;
+mod overlay;
+mod validation;
+use error::{ApiError, ApiJson, ApiPath};
+use validation::{FieldErrors, ValidJson, Validate};
+
+pub use middleware::{RateLimit, RateLimits};
+
+/// Written while the server runs so local clients (`yt-hear ctl`) can find the port.
+const DISCOVERY_FILE: &str = "server.json";
+const UNIX_SOCKET_FILE: &str = "api.sock";
+
+/// Where the API listens: TCP on the given port, a Unix socket in the runtime dir, or both.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiListen {
+    #[default]
+    Tcp,
+    Unix,
+    Both,
+}
+
+impl ApiListen {
+    pub const ALL: [ApiListen; 3] = [Self::Tcp, Self::Unix, Self::Both];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::Tcp => "tcp",
+            Self::Unix => "unix",
+            Self::Both => "both",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Tcp => "TCP Port",
+            Self::Unix => "Unix Socket",
+            Self::Both => "TCP Port and Unix Socket",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|l| l.key() == key)
+    }
+
+    fn tcp(self) -> bool {
+        matches!(self, Self::Tcp | Self::Both)
+    }
+
+    fn unix(self) -> bool {
+        matches!(self, Self::Unix | Self::Both)
+    }
+}
+
+pub fn unix_socket_path() -> std::path::PathBuf {
+    crate::single_instance::runtime_dir().join(UNIX_SOCKET_FILE)
+}
+
+pub fn discovery_path() -> std::path::PathBuf {
+    crate::single_instance::runtime_dir().join(DISCOVERY_FILE)
+}
+
+fn write_discovery(port: Option<u16>, socket: Option<&std::path::Path>) -> std::io::Result<()> {
+    let path = discovery_path();
+    if let Some(dir) = path.parent() {
+        std::fs::create_dir_all(dir)?;
+    }
+    let data = json!({ "port": port, "socket": socket, "pid": std::process::id() }).to_string();
+    std::fs::write(&path, data)
+}
 
 // --- Structs de Payload ---
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct SeekPayload {
+    /// Seconds, finite and not negative.
     seconds: f64,
 }
 
-#[derive(Deserialize)]
+impl Validate for SeekPayload {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.check(
+            self.seconds.is_finite() && self.seconds >= 0.0,
+            "seconds",
+            "must be a finite number of seconds, 0 or more",
+        );
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
 struct VolumePayload {
+    /// 0 to 100.
     volume: f64,
 }
 
-#[derive(Deserialize)]
+impl Validate for VolumePayload {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.check(
+            (0.0..=100.0).contains(&self.volume),
+            "volume",
+            "must be between 0 and 100",
+        );
+    }
+}
+
+/// Where `addToQueue` puts the video; the values are the ones YouTube Music uses.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+enum InsertPosition {
+    #[default]
+    InsertAtEnd,
+    InsertAfterCurrentVideo,
+}
+
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct QueueAddPayload {
+    /// 11-character YouTube video id.
+    #[schema(example = "dQw4w9WgXcQ")]
     video_id: String,
-    insert_position: Option<String>,
+    #[serde(default)]
+    insert_position: InsertPosition,
 }
 
-#[derive(Deserialize)]
+impl Validate for QueueAddPayload {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.check(
+            validation::is_video_id(&self.video_id),
+            "videoId",
+            "must be an 11-character YouTube video id",
+        );
+    }
+}
+
+#[derive(Deserialize, ToSchema)]
 struct QueueIndexPayload {
+    /// Must be below the current queue length.
     index: usize,
 }
 
-#[derive(Deserialize)]
+#[derive(Deserialize, ToSchema)]
 struct SearchPayload {
     query: String,
 }
-#[derive(Deserialize)]
+
+impl Validate for SearchPayload {
+    fn validate(&self, errors: &mut FieldErrors) {
+        errors.check(!self.query.trim().is_empty(), "query", "must not be empty");
+    }
+}
+#[derive(Deserialize, ToSchema)]
 #[serde(rename_all = "camelCase")]
 struct QueueMovePayload {
     from_index: usize,
     to_index: usize,
 }
+
+#[derive(Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+struct AdBlockExplainPayload {
+    url: String,
+    #[serde(default)]
+    source_url: String,
+    #[serde(default)]
+    request_type: String,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct AdBlockDisabledPayload {
+    disabled: bool,
+}
+
+#[derive(Deserialize, ToSchema)]
+struct ScriptEnabledPayload {
+    /// Script key as listed by `GET /scripts`, e.g. `adblock`.
+    id: String,
+    enabled: bool,
+}
+
+// --- Structs de respuesta (solo documentación OpenAPI, nunca se construyen) ---
+/// Returned by command endpoints once the action was sent to the player.
+#[allow(dead_code)]
+#[derive(Serialize, ToSchema)]
+struct StatusResponse {
+    #[schema(example = "ok")]
+    status: String,
+}
+
+/// Answers of every endpoint that forwards a command to the player.
+#[allow(dead_code)]
+#[derive(IntoResponses)]
+enum CommandResponses {
+    /// Command sent to the player
+    #[response(status = 200)]
+    Sent(StatusResponse),
+    /// The player page is not loaded
+    #[response(status = 503)]
+    PlayerUnavailable(ApiError),
+}
+
+/// Rejections of endpoints that take a JSON body.
+#[allow(dead_code)]
+#[derive(IntoResponses)]
+enum BodyErrors {
+    /// Malformed JSON body
+    #[response(status = 400)]
+    MalformedJson(ApiError),
+    /// Missing JSON content type
+    #[response(status = 415)]
+    UnsupportedMediaType(ApiError),
+    /// Body does not match the payload schema or a field is invalid
+    #[response(status = 422)]
+    ValidationFailed(ApiError),
+}
+
+/// Failures of endpoints that ask the player page for live data.
+#[allow(dead_code)]
+#[derive(IntoResponses)]
+enum LiveDataErrors {
+    /// The player reported an error
+    #[response(status = 502)]
+    PlayerError(ApiError),
+    /// The player did not answer in time
+    #[response(status = 504)]
+    PlayerTimeout(ApiError),
+}
+
+/// Failure of endpoints backed by Rust-side state (adblock, script health).
+#[allow(dead_code)]
+#[derive(IntoResponses)]
+enum SubsystemErrors {
+    /// Subsystem not initialized yet
+    #[response(status = 503)]
+    NotInitialized(ApiError),
+}
+
 // --- HANDLERS GET ---
+#[utoipa::path(
+    post,
+    path = "/queue/move",
+    tag = "queue",
+    summary = "Move a queue item",
+    request_body = QueueMovePayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
 async fn move_queue_item(
     State(state): State<AppState>,
-    Json(payload): Json<QueueMovePayload>,
-) -> Json<Value> {
+    ApiJson(payload): ApiJson<QueueMovePayload>,
+) -> Result<Json<Value>, ApiError> {
+    validation::check_queue_indices(
+        &state,
+        &[
+            ("fromIndex", payload.from_index),
+            ("toIndex", payload.to_index),
+        ],
+    )
+    .await?;
     emit_cmd(
         &state,
         json!({
@@ -58,188 +288,779 @@ async fn move_queue_item(
             "toIndex": payload.to_index
         }),
     )
-    .await;
-    Json(json!({ "status": "ok" }))
+    .await?;
+    validation::invalidate_queue(&state).await;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
-async fn toggle_shuffle(State(state): State<AppState>) -> Json<Value> {
+#[utoipa::path(
+    post,
+    path = "/shuffle",
+    tag = "playback",
+    summary = "Toggle shuffle",
+    responses(CommandResponses,)
+)]
+async fn toggle_shuffle(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
     // Necesitas implementar esto en JS también (ver paso 4)
-    emit_cmd(&state, json!({ "action": "toggleShuffle" })).await;
-    Json(json!({ "status": "ok" }))
+    emit_cmd(&state, json!({ "action": "toggleShuffle" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
-async fn toggle_repeat(State(state): State<AppState>) -> Json<Value> {
+#[utoipa::path(
+    post,
+    path = "/repeat",
+    tag = "playback",
+    summary = "Switch repeat mode",
+    responses(CommandResponses,)
+)]
+async fn toggle_repeat(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
     // Necesitas implementar esto en JS también (ver paso 4)
-    emit_cmd(&state, json!({ "action": "toggleRepeat" })).await;
-    Json(json!({ "status": "ok" }))
-}
-async fn get_song(State(state): State<AppState>) -> Result<Json<Value>, (StatusCode, String)> {
-    match state.request_live_data("get-song-info", 1000).await {
-        Ok(data) => Ok(Json(data)),
-        Err(e) => Err((
-            StatusCode::GATEWAY_TIMEOUT,
-            json!({ "error": e }).to_string(),
-        )),
+    emit_cmd(&state, json!({ "action": "toggleRepeat" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
+}
+#[utoipa::path(
+    get,
+    path = "/song",
+    tag = "playback",
+    summary = "Current song",
+    responses(
+        (status = 200, description = "Live data from the player", body = Value),
+        LiveDataErrors,
+    )
+)]
+async fn get_song(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    live_data(&state, "get-song-info", 1000).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/queue",
+    tag = "queue",
+    summary = "Current queue",
+    responses(
+        (status = 200, description = "Live data from the player", body = Value),
+        LiveDataErrors,
+    )
+)]
+async fn get_queue(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    let queue = live_data(&state, "get-queue", 2000).await?;
+    validation::cache_queue(&state, &queue).await;
+    Ok(queue)
+}
+
+#[utoipa::path(
+    get,
+    path = "/volume",
+    tag = "volume",
+    summary = "Current volume",
+    responses(
+        (status = 200, description = "Live data from the player", body = Value),
+        LiveDataErrors,
+    )
+)]
+async fn get_volume(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    live_data(&state, "get-volume", 1000).await
+}
+
+/// Runs `f` against Rust-side state managed by Tauri (adblock, script health, ...).
+async fn with_managed<S, T>(
+    state: &AppState,
+    name: &str,
+    f: impl FnOnce(&S) -> T,
+) -> Result<T, ApiError>
+where
+    S: Send + Sync + 'static,
+{
+    let handle_guard = state.app_handle.lock().await;
+    match handle_guard
+        .as_ref()
+        .and_then(|handle| handle.try_state::<S>())
+    {
+        Some(managed) => Ok(f(&managed)),
+        None => Err(ApiError::unavailable(format!("{} not initialized", name))),
     }
 }
 
-async fn get_queue(State(state): State<AppState>) -> Result<Json<Value>, (StatusCode, String)> {
-    match state.request_live_data("get-queue", 2000).await {
-        Ok(data) => Ok(Json(data)),
-        Err(e) => Err((
-            StatusCode::GATEWAY_TIMEOUT,
-            json!({ "error": e }).to_string(),
-        )),
+async fn with_adblock<T>(
+    state: &AppState,
+    f: impl FnOnce(&AdBlockState) -> T,
+) -> Result<T, ApiError> {
+    with_managed(state, "AdBlock", f).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/health",
+    tag = "diagnostics",
+    summary = "Script injection health",
+    responses(
+        (status = 200, body = Value),
+        SubsystemErrors,
+    )
+)]
+async fn get_health(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    with_managed(&state, "Script health", |health: &ScriptHealth| {
+        Json(health.snapshot())
+    })
+    .await
+}
+
+#[utoipa::path(
+    get,
+    path = "/scripts",
+    tag = "diagnostics",
+    summary = "Built-in scripts, their enabled flag and dependencies",
+    responses(
+        (status = 200, body = Value),
+        SubsystemErrors,
+    )
+)]
+async fn get_scripts(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    with_managed(&state, "Script settings", |settings: &ScriptSettings| {
+        Json(json!(settings.list()))
+    })
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/scripts/enabled",
+    tag = "diagnostics",
+    summary = "Enable or disable a built-in script and reload the page",
+    request_body = ScriptEnabledPayload,
+    responses(
+        (status = 200, body = Value),
+        (status = 404, description = "No built-in script has that id", body = ApiError),
+        (status = 409, description = "Other scripts depend on it, so it cannot be disabled", body = ApiError),
+        BodyErrors,
+        SubsystemErrors,
+    )
+)]
+async fn set_script_enabled(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<ScriptEnabledPayload>,
+) -> Result<Json<Value>, ApiError> {
+    let id = ScriptId::from_key(&payload.id)
+        .ok_or_else(|| ApiError::not_found(format!("Unknown script: {}", payload.id)))?;
+    if !payload.enabled && id.is_required() {
+        return Err(ApiError::new(
+            StatusCode::CONFLICT,
+            "script_required",
+            format!("{} is required by other scripts", payload.id),
+        ));
     }
+    let handle = state.app_handle.lock().await.clone();
+    let handle = handle.ok_or_else(|| ApiError::unavailable("AppHandle no inicializado"))?;
+    crate::scripts::settings::set_script_enabled(&handle, id, payload.enabled)
+        .map(|scripts| Json(json!(scripts)))
+        .map_err(ApiError::unavailable)
+}
+
+#[utoipa::path(
+    get,
+    path = "/plugins",
+    tag = "diagnostics",
+    summary = "User plugins found at the last scan",
+    responses(
+        (status = 200, body = Value),
+        SubsystemErrors,
+    )
+)]
+async fn get_plugins(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    with_managed(&state, "Plugins", |registry: &PluginRegistry| {
+        Json(json!(registry.reports()))
+    })
+    .await
+}
+
+#[utoipa::path(
+    post,
+    path = "/plugins/reload",
+    tag = "diagnostics",
+    summary = "Rescan the plugin folder",
+    responses(
+        (status = 200, body = Value),
+        SubsystemErrors,
+    )
+)]
+async fn reload_plugins(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    let handle = state.app_handle.lock().await.clone();
+    let handle = handle.ok_or_else(|| ApiError::unavailable("AppHandle no inicializado"))?;
+    crate::scripts::plugins::reload_plugins(&handle)
+        .map(|plugins| Json(json!(plugins)))
+        .map_err(ApiError::unavailable)
 }
 
-async fn get_volume(State(state): State<AppState>) -> Result<Json<Value>, (StatusCode, String)> {
-    match state.request_live_data("get-volume", 1000).await {
-        Ok(data) => Ok(Json(data)),
-        Err(e) => Err((
-            StatusCode::GATEWAY_TIMEOUT,
-            json!({ "error": e }).to_string(),
+#[utoipa::path(
+    get,
+    path = "/lyrics",
+    tag = "lyrics",
+    summary = "Lyrics of the current song",
+    responses(
+        (status = 200, description = "Lyrics, with synced lines when the provider has them", body = Lyrics),
+        (status = 404, description = "Nothing is playing or no provider has lyrics", body = ApiError),
+        (status = 502, description = "Every lyrics provider failed; the failure is kept for a minute", body = ApiError),
+        (status = 503, description = "Lyrics are disabled in the preferences", body = ApiError),
+        (status = 504, description = "The player did not answer in time", body = ApiError),
+    )
+)]
+async fn get_lyrics(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    if !with_managed(&state, "Lyrics", LyricsStore::is_enabled).await? {
+        return Err(ApiError::unavailable(
+            "Lyrics are disabled in the preferences",
+        ));
+    }
+    let Json(song) = live_data(&state, "get-song-info", 1000).await?;
+    let query =
+        TrackQuery::from_song(&song).ok_or_else(|| ApiError::not_found("Nothing is playing"))?;
+
+    let handle = state.app_handle.lock().await.clone();
+    let handle = handle.ok_or_else(|| ApiError::unavailable("AppHandle no inicializado"))?;
+    // Provider lookups are blocking HTTP calls
+    let lookup_query = query.clone();
+    let result =
+        tokio::task::spawn_blocking(move || handle.state::<LyricsStore>().lookup(&lookup_query))
+            .await
+            .map_err(|e| ApiError::unavailable(e.to_string()))?;
+
+    match result {
+        Ok(Some(lyrics)) => Ok(Json(json!(lyrics))),
+        Ok(None) => Err(ApiError::not_found(format!(
+            "No lyrics found for {} - {}",
+            query.artist, query.title
+        ))),
+        Err(e) => Err(ApiError::new(
+            StatusCode::BAD_GATEWAY,
+            "lyrics_unavailable",
+            e,
         )),
     }
 }
 
+#[utoipa::path(
+    get,
+    path = "/adblock/stats",
+    tag = "adblock",
+    summary = "Adblock statistics",
+    responses(
+        (status = 200, body = Value),
+        SubsystemErrors,
+    )
+)]
+async fn get_adblock_stats(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    let adblock = with_adblock(&state, AdBlockState::clone).await?;
+    // Naming the logged rules may build the rule engine
+    tokio::task::spawn_blocking(move || Json(adblock.stats_json()))
+        .await
+        .map_err(|e| ApiError::unavailable(e.to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/adblock/explain",
+    tag = "adblock",
+    summary = "Explain the adblock decision for a URL",
+    request_body = AdBlockExplainPayload,
+    responses(
+        (status = 200, body = Value),
+        BodyErrors,
+        SubsystemErrors,
+    )
+)]
+async fn explain_adblock_url(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<AdBlockExplainPayload>,
+) -> Result<Json<Value>, ApiError> {
+    let adblock = with_adblock(&state, AdBlockState::clone).await?;
+    tokio::task::spawn_blocking(move || {
+        Json(adblock.explain_url(&payload.url, &payload.source_url, &payload.request_type))
+    })
+    .await
+    .map_err(|e| ApiError::unavailable(e.to_string()))
+}
+
+#[utoipa::path(
+    post,
+    path = "/adblock/disabled",
+    tag = "adblock",
+    summary = "Disable adblock for this session",
+    request_body = AdBlockDisabledPayload,
+    responses(
+        (status = 200, body = Value),
+        BodyErrors,
+        SubsystemErrors,
+    )
+)]
+async fn set_adblock_disabled(
+    State(state): State<AppState>,
+    ApiJson(payload): ApiJson<AdBlockDisabledPayload>,
+) -> Result<Json<Value>, ApiError> {
+    let response = with_adblock(&state, |adblock| {
+        adblock.set_disabled(payload.disabled);
+        Json(json!({ "status": "ok", "disabled": adblock.is_disabled() }))
+    })
+    .await?;
+    if let Some(app) = state.app_handle.lock().await.as_ref() {
+        crate::tray::sync_block_ads(app);
+    }
+    Ok(response)
+}
+
 // --- HANDLERS DE COMANDOS ---
 
-async fn emit_cmd(state: &AppState, action: Value) {
-    state.emit_to_frontend("ytm:command", action).await;
+async fn emit_cmd(state: &AppState, action: Value) -> Result<(), ApiError> {
+    state
+        .emit_to_frontend("ytm:command", action)
+        .await
+        .map_err(ApiError::unavailable)
+}
+
+/// Live data from the player page; `{ error }` answers become 502s.
+async fn live_data(
+    state: &AppState,
+    topic: &str,
+    timeout_ms: u64,
+) -> Result<Json<Value>, ApiError> {
+    let data = state
+        .request_live_data(topic, timeout_ms)
+        .await
+        .map_err(ApiError::player_timeout)?;
+    player_answer(data)
+}
+
+fn player_answer(data: Value) -> Result<Json<Value>, ApiError> {
+    if let Some(error) = data.get("error").and_then(Value::as_str) {
+        return Err(ApiError::new(
+            StatusCode::BAD_GATEWAY,
+            "player_error",
+            error,
+        ));
+    }
+    Ok(Json(data))
 }
 
 // Wrappers
 // CORRECCIÓN: Se añade .await a todas las llamadas emit_cmd
-async fn next(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "next" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/next",
+    tag = "playback",
+    summary = "Next track",
+    responses(CommandResponses,)
+)]
+async fn next(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "next" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
-async fn previous(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "previous" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/previous",
+    tag = "playback",
+    summary = "Previous track",
+    responses(CommandResponses,)
+)]
+async fn previous(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "previous" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
-async fn play(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "play" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/play",
+    tag = "playback",
+    summary = "Resume playback",
+    responses(CommandResponses,)
+)]
+async fn play(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "play" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
-async fn pause(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "pause" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/pause",
+    tag = "playback",
+    summary = "Pause playback",
+    responses(CommandResponses,)
+)]
+async fn pause(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "pause" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
-async fn toggle_play(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "playPause" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/toggle-play",
+    tag = "playback",
+    summary = "Toggle play/pause",
+    responses(CommandResponses,)
+)]
+async fn toggle_play(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "playPause" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
-async fn like(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "like" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/like",
+    tag = "playback",
+    summary = "Like the current track",
+    responses(CommandResponses,)
+)]
+async fn like(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "like" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
-async fn dislike(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "dislike" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/dislike",
+    tag = "playback",
+    summary = "Dislike the current track",
+    responses(CommandResponses,)
+)]
+async fn dislike(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "dislike" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
-async fn toggle_mute(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "toggleMute" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/toggle-mute",
+    tag = "volume",
+    summary = "Toggle mute",
+    responses(CommandResponses,)
+)]
+async fn toggle_mute(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "toggleMute" })).await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
-async fn clear_queue(State(state): State<AppState>) -> Json<Value> {
-    emit_cmd(&state, json!({ "action": "clearQueue" })).await;
-    Json(json!({ "status": "ok" }))
+#[utoipa::path(
+    post,
+    path = "/clear-queue",
+    tag = "queue",
+    summary = "Clear the queue",
+    responses(CommandResponses,)
+)]
+async fn clear_queue(State(state): State<AppState>) -> Result<Json<Value>, ApiError> {
+    emit_cmd(&state, json!({ "action": "clearQueue" })).await?;
+    validation::invalidate_queue(&state).await;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
 // Comandos con argumentos
-async fn seek_to(State(state): State<AppState>, Json(payload): Json<SeekPayload>) -> Json<Value> {
+#[utoipa::path(
+    post,
+    path = "/seek-to",
+    tag = "playback",
+    summary = "Seek to an absolute position",
+    request_body = SeekPayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
+async fn seek_to(
+    State(state): State<AppState>,
+    ValidJson(payload): ValidJson<SeekPayload>,
+) -> Result<Json<Value>, ApiError> {
     emit_cmd(
         &state,
         json!({ "action": "seek", "value": payload.seconds }),
     )
-    .await;
-    Json(json!({ "status": "ok" }))
+    .await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
-async fn go_back(State(state): State<AppState>, Json(payload): Json<SeekPayload>) -> Json<Value> {
+#[utoipa::path(
+    post,
+    path = "/go-back",
+    tag = "playback",
+    summary = "Seek backwards",
+    request_body = SeekPayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
+async fn go_back(
+    State(state): State<AppState>,
+    ValidJson(payload): ValidJson<SeekPayload>,
+) -> Result<Json<Value>, ApiError> {
     emit_cmd(
         &state,
         json!({ "action": "goBack", "value": payload.seconds }),
     )
-    .await;
-    Json(json!({ "status": "ok" }))
+    .await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/go-forward",
+    tag = "playback",
+    summary = "Seek forwards",
+    request_body = SeekPayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
 async fn go_forward(
     State(state): State<AppState>,
-    Json(payload): Json<SeekPayload>,
-) -> Json<Value> {
+    ValidJson(payload): ValidJson<SeekPayload>,
+) -> Result<Json<Value>, ApiError> {
     emit_cmd(
         &state,
         json!({ "action": "goForward", "value": payload.seconds }),
     )
-    .await;
-    Json(json!({ "status": "ok" }))
+    .await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/volume",
+    tag = "volume",
+    summary = "Set the volume",
+    request_body = VolumePayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
 async fn set_volume(
     State(state): State<AppState>,
-    Json(payload): Json<VolumePayload>,
-) -> Json<Value> {
+    ValidJson(payload): ValidJson<VolumePayload>,
+) -> Result<Json<Value>, ApiError> {
     emit_cmd(
         &state,
         json!({ "action": "setVolume", "value": payload.volume }),
     )
-    .await;
-    Json(json!({ "status": "ok" }))
+    .await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
+#[utoipa::path(
+    post,
+    path = "/queue",
+    tag = "queue",
+    summary = "Add a video to the queue",
+    request_body = QueueAddPayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
 async fn add_to_queue(
     State(state): State<AppState>,
-    Json(payload): Json<QueueAddPayload>,
-) -> Json<Value> {
+    ValidJson(payload): ValidJson<QueueAddPayload>,
+) -> Result<Json<Value>, ApiError> {
     emit_cmd(
         &state,
         json!({
             "action": "addToQueue",
             "videoId": payload.video_id,
-            "insertPosition": payload.insert_position.unwrap_or("INSERT_AT_END".to_string())
+            "insertPosition": payload.insert_position
         }),
     )
-    .await;
-    Json(json!({ "status": "ok" }))
+    .await?;
+    validation::invalidate_queue(&state).await;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
-async fn remove_queue_item(State(state): State<AppState>, Path(index): Path<usize>) -> Json<Value> {
+#[utoipa::path(
+    delete,
+    path = "/queue/{index}",
+    tag = "queue",
+    summary = "Remove a queue item",
+    params(
+        ("index" = usize, Path, description = "Position in the queue"),
+    ),
+    responses(
+        CommandResponses,
+        (status = 400, description = "Index is not a number", body = ApiError),
+        (status = 422, description = "Index is past the end of the queue", body = ApiError),
+    )
+)]
+async fn remove_queue_item(
+    State(state): State<AppState>,
+    ApiPath(index): ApiPath<usize>,
+) -> Result<Json<Value>, ApiError> {
+    validation::check_queue_indices(&state, &[("index", index)]).await?;
     emit_cmd(
         &state,
         json!({ "action": "removeFromQueue", "value": index }),
     )
-    .await;
-    Json(json!({ "status": "ok" }))
+    .await?;
+    validation::invalidate_queue(&state).await;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
+#[utoipa::path(
+    patch,
+    path = "/queue",
+    tag = "queue",
+    summary = "Play the queue item at an index",
+    request_body = QueueIndexPayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
 async fn set_queue_index(
     State(state): State<AppState>,
-    Json(payload): Json<QueueIndexPayload>,
-) -> Json<Value> {
+    ApiJson(payload): ApiJson<QueueIndexPayload>,
+) -> Result<Json<Value>, ApiError> {
+    validation::check_queue_indices(&state, &[("index", payload.index)]).await?;
     emit_cmd(
         &state,
         json!({ "action": "setQueueIndex", "value": payload.index }),
     )
-    .await;
-    Json(json!({ "status": "ok" }))
+    .await?;
+    Ok(Json(json!({ "status": "ok" })))
 }
 
-async fn search(State(state): State<AppState>, Json(payload): Json<SearchPayload>) -> Json<Value> {
+#[utoipa::path(
+    post,
+    path = "/search",
+    tag = "search",
+    summary = "Search YouTube Music",
+    request_body = SearchPayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
+async fn search(
+    State(state): State<AppState>,
+    ValidJson(payload): ValidJson<SearchPayload>,
+) -> Result<Json<Value>, ApiError> {
     emit_cmd(
         &state,
         json!({ "action": "search", "query": payload.query }),
     )
-    .await;
-    Json(json!({ "status": "triggered" }))
+    .await?;
+    Ok(Json(json!({ "status": "triggered" })))
+}
+
+/// Same as `PATCH /queue`; kept for existing clients.
+#[utoipa::path(
+    post,
+    path = "/queue/index",
+    tag = "queue",
+    summary = "Play the queue item at an index (alias of PATCH /queue)",
+    request_body = QueueIndexPayload,
+    responses(
+        CommandResponses,
+        BodyErrors,
+    )
+)]
+async fn set_queue_index_legacy(
+    state: State<AppState>,
+    payload: ApiJson<QueueIndexPayload>,
+) -> Result<Json<Value>, ApiError> {
+    set_queue_index(state, payload).await
+}
+
+#[utoipa::path(
+    get,
+    path = "/openapi.json",
+    tag = "diagnostics",
+    summary = "This OpenAPI document",
+    responses(
+        (status = 200, description = "OpenAPI 3 document", body = Value),
+    )
+)]
+async fn get_openapi() -> Json<utoipa::openapi::OpenApi> {
+    Json(ApiDoc::openapi())
+}
+
+async fn route_not_found(uri: axum::http::Uri) -> ApiError {
+    ApiError::not_found(format!("No route for {}", uri.path()))
+}
+
+// --- OPENAPI ---
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "yt-hear API",
+        description = "Controls the YouTube Music player running in yt-hear."
+    ),
+    servers((url = "/api/v1")),
+    paths(
+        get_song, get_queue, add_to_queue, set_queue_index, remove_queue_item,
+        set_queue_index_legacy, move_queue_item, clear_queue, get_volume, set_volume,
+        toggle_mute, play, pause, toggle_play, next, previous, seek_to, go_back,
+        go_forward, like, dislike, search, toggle_shuffle, toggle_repeat,
+        get_adblock_stats, explain_adblock_url, set_adblock_disabled, get_health,
+        get_scripts, set_script_enabled, get_plugins, reload_plugins, get_lyrics, get_openapi,
+    ),
+    components(schemas(
+        SeekPayload, VolumePayload, QueueAddPayload, InsertPosition, QueueIndexPayload, SearchPayload,
+        QueueMovePayload, AdBlockExplainPayload, AdBlockDisabledPayload, ScriptEnabledPayload,
+        StatusResponse,
+        Lyrics, LyricLine, ApiError,
+    ))
+)]
+struct ApiDoc;
+
+/// Every `/api/v1` route as `(method, path, handler)`. The router is built from
+/// this list and a test checks each entry against [`ApiDoc`].
+fn api_routes() -> Vec<(&'static str, &'static str, MethodRouter<AppState>)> {
+    vec![
+        ("get", "/song", get(get_song)),
+        ("get", "/queue", get(get_queue)),
+        ("post", "/queue", post(add_to_queue)),
+        ("patch", "/queue", patch(set_queue_index)),
+        ("delete", "/queue/:index", delete(remove_queue_item)),
+        ("post", "/queue/index", post(set_queue_index_legacy)),
+        ("post", "/queue/move", post(move_queue_item)),
+        ("get", "/volume", get(get_volume)),
+        ("post", "/volume", post(set_volume)),
+        ("post", "/toggle-mute", post(toggle_mute)),
+        ("post", "/play", post(play)),
+        ("post", "/pause", post(pause)),
+        ("post", "/toggle-play", post(toggle_play)),
+        ("post", "/next", post(next)),
+        ("post", "/previous", post(previous)),
+        ("post", "/seek-to", post(seek_to)),
+        ("post", "/go-back", post(go_back)),
+        ("post", "/go-forward", post(go_forward)),
+        ("post", "/like", post(like)),
+        ("post", "/dislike", post(dislike)),
+        ("post", "/search", post(search)),
+        ("post", "/shuffle", post(toggle_shuffle)),
+        ("post", "/repeat", post(toggle_repeat)),
+        ("post", "/clear-queue", post(clear_queue)),
+        ("get", "/adblock/stats", get(get_adblock_stats)),
+        ("post", "/adblock/explain", post(explain_adblock_url)),
+        ("post", "/adblock/disabled", post(set_adblock_disabled)),
+        ("get", "/health", get(get_health)),
+        ("get", "/scripts", get(get_scripts)),
+        ("post", "/scripts/enabled", post(set_script_enabled)),
+        ("get", "/plugins", get(get_plugins)),
+        ("post", "/plugins/reload", post(reload_plugins)),
+        ("get", "/lyrics", get(get_lyrics)),
+        ("get", "/openapi.json", get(get_openapi)),
+    ]
 }
 
 // --- SERVER ---
 
-pub async fn start_server(port: u16, app_state: Arc<AppState>) -> Result<String, String> {
+/// The `/api/v1` routes; unknown paths and methods answer with [`ApiError`]s.
+fn api_router() -> Router<AppState> {
+    // Routes registered twice for one path are merged by method
+    api_routes()
+        .into_iter()
+        .fold(Router::new(), |router, (_, path, handler)| {
+            router.route(path, handler)
+        })
+        .fallback(route_not_found)
+        .layer(axum::middleware::map_response(error::method_not_allowed))
+}
+
+pub async fn start_server(
+    port: u16,
+    listen: ApiListen,
+    limits: RateLimits,
+    app_state: Arc<AppState>,
+) -> Result<String, String> {
     {
         let shutdown_guard = app_state.http_server_shutdown.lock().await;
         if shutdown_guard.is_some() {
@@ -247,40 +1068,47 @@ pub async fn start_server(port: u16, app_state: Arc<AppState>) -> Result<String,
         }
     }
 
-    let api_v1: Router<AppState> = Router::new()
-        .route("/song", get(get_song))
-        .route("/queue", get(get_queue).post(add_to_queue))
-        .route("/queue", patch(set_queue_index))
-        .route("/queue/:index", delete(remove_queue_item))
-        .route("/queue/index", post(set_queue_index))
-        .route("/queue/move", post(move_queue_item)) // NUEVA RUTA
-        .route("/volume", get(get_volume).post(set_volume))
-        .route("/toggle-mute", post(toggle_mute))
-        .route("/play", post(play))
-        .route("/pause", post(pause))
-        .route("/toggle-play", post(toggle_play))
-        .route("/next", post(next))
-        .route("/previous", post(previous))
-        .route("/seek-to", post(seek_to))
-        .route("/go-back", post(go_back))
-        .route("/go-forward", post(go_forward))
-        .route("/like", post(like))
-        .route("/dislike", post(dislike))
-        .route("/search", post(search))
-        .route("/shuffle", post(toggle_shuffle))
-        .route("/repeat", post(toggle_repeat))
-        .route("/clear-queue", post(clear_queue));
+    let log_dir = app_state
+        .app_handle
+        .lock()
+        .await
+        .as_ref()
+        .and_then(|app| app.path().app_log_dir().ok());
+    let mw = middleware::Middleware::new(limits, log_dir);
+    // Last layer runs first: CORS, then the access log (so 429s are logged), then the limiter
+    // One shutdown signal fans out to every listener and open overlay streams
+    let (stop_tx, stop_rx) = watch::channel(false);
     let app = Router::new()
-        .nest("/api/v1", api_v1)
+        .nest("/api/v1", api_router())
+        .merge(overlay::routes(stop_rx.clone()))
+        .layer(axum::middleware::from_fn_with_state(
+            mw.clone(),
+            middleware::rate_limit,
+        ))
+        .layer(axum::middleware::from_fn_with_state(
+            mw,
+            middleware::access_log,
+        ))
         .layer(CorsLayer::permissive())
         .with_state((*app_state).clone());
 
-    let addr = SocketAddr::from(([0, 0, 0, 0], port));
-    println!("🚀 Starting HTTP Server on http://{}", addr);
-
-    let listener = tokio::net::TcpListener::bind(addr)
-        .await
-        .map_err(|e| format!("Failed to bind port {}: {}", port, e))?;
+    let tcp_listener = if listen.tcp() {
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        println!("🚀 Starting HTTP Server on http://{}", addr);
+        let listener = tokio::net::TcpListener::bind(addr)
+            .await
+            .map_err(|e| format!("Failed to bind port {}: {}", port, e))?;
+        Some(listener)
+    } else {
+        None
+    };
+    let socket_path = unix_socket_path();
+    let unix_listener = if listen.unix() {
+        println!("🚀 Starting HTTP Server on unix:{}", socket_path.display());
+        Some(unix_socket::bind(&socket_path)?)
+    } else {
+        None
+    };
 
     let (tx, rx) = oneshot::channel();
     {
@@ -288,19 +1116,56 @@ pub async fn start_server(port: u16, app_state: Arc<AppState>) -> Result<String,
         *shutdown_guard = Some(tx);
     }
 
-    tokio::spawn(async move {
-        if let Err(e) = axum::serve(listener, app)
-            .with_graceful_shutdown(async {
-                rx.await.ok();
-            })
-            .await
-        {
-            eprintln!("❌ HTTP Server Error: {}", e);
-        }
+    if let Err(e) = write_discovery(
+        listen.tcp().then_some(port),
+        listen.unix().then_some(socket_path.as_path()),
+    ) {
+        eprintln!("⚠️ Could not write {}: {}", DISCOVERY_FILE, e);
+    }
+
+    let task = tokio::spawn(async move {
+        let stop = async move {
+            rx.await.ok();
+            let _ = stop_tx.send(true);
+        };
+        let tcp = async {
+            let Some(listener) = tcp_listener else {
+                return;
+            };
+            let mut stop_rx = stop_rx.clone();
+            let service = app
+                .clone()
+                .into_make_service_with_connect_info::<SocketAddr>();
+            if let Err(e) = axum::serve(listener, service)
+                .with_graceful_shutdown(async move {
+                    let _ = stop_rx.changed().await;
+                })
+                .await
+            {
+                eprintln!("❌ HTTP Server Error: {}", e);
+            }
+        };
+        let unix = async {
+            if let Some(listener) = unix_listener {
+                unix_socket::serve(listener, &socket_path, app.clone(), stop_rx.clone()).await;
+            }
+        };
+        tokio::join!(stop, tcp, unix);
+
+        let _ = std::fs::remove_file(discovery_path());
         println!("🛑 HTTP Server Stopped");
     });
+    *app_state.http_server_task.lock().await = Some(task);
 
-    Ok(format!("Server running on port {}", port))
+    Ok(match listen {
+        ApiListen::Tcp => format!("Server running on port {}", port),
+        ApiListen::Unix => format!("Server running on {}", unix_socket_path().display()),
+        ApiListen::Both => format!(
+            "Server running on port {} and {}",
+            port,
+            unix_socket_path().display()
+        ),
+    })
 }
 
 pub async fn stop_server(app_state: &AppState) -> Result<String, String> {
@@ -313,3 +1178,139 @@ pub async fn stop_server(app_state: &AppState) -> Result<String, String> {
         Err("Server is not running".to_string())
     }
 }
+
+/// Serves the API router over a Unix domain socket (axum 0.7 only serves TCP itself).
+#[cfg(unix)]
+mod unix_socket {
+    use axum::Router;
+    use hyper::server::conn::http1;
+    use hyper_util::rt::TokioIo;
+    use hyper_util::service::TowerToHyperService;
+    use std::os::unix::fs::PermissionsExt;
+    use std::path::Path;
+    use tokio::net::UnixListener;
+    use tokio::sync::watch;
+
+    pub type Listener = UnixListener;
+
+    /// Binds `path` readable and writable by the current user only.
+    pub fn bind(path: &Path) -> Result<Listener, String> {
+        let fail = |e: std::io::Error| format!("Failed to bind {}: {}", path.display(), e);
+        if let Some(dir) = path.parent() {
+            std::fs::create_dir_all(dir).map_err(fail)?;
+            std::fs::set_permissions(dir, std::fs::Permissions::from_mode(0o700)).map_err(fail)?;
+        }
+        // A socket file left over by a crashed instance would make bind fail
+        let _ = std::fs::remove_file(path);
+        let listener = UnixListener::bind(path).map_err(fail)?;
+        std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600)).map_err(fail)?;
+        Ok(listener)
+    }
+
+    pub async fn serve(
+        listener: Listener,
+        path: &Path,
+        app: Router,
+        mut stop: watch::Receiver<bool>,
+    ) {
+        loop {
+            let stream = tokio::select! {
+                accepted = listener.accept() => match accepted {
+                    Ok((stream, _)) => stream,
+                    Err(e) => {
+                        eprintln!("❌ Unix socket accept error: {}", e);
+                        continue;
+                    }
+                },
+                _ = stop.changed() => break,
+            };
+
+            let service = TowerToHyperService::new(app.clone());
+            tokio::spawn(async move {
+                if let Err(e) = http1::Builder::new()
+                    .serve_connection(TokioIo::new(stream), service)
+                    .await
+                {
+                    eprintln!("❌ Unix socket connection error: {}", e);
+                }
+            });
+        }
+        let _ = std::fs::remove_file(path);
+    }
+}
+
+#[cfg(not(unix))]
+mod unix_socket {
+    use axum::Router;
+    use std::path::Path;
+    use tokio::sync::watch;
+
+    pub enum Listener {}
+
+    pub fn bind(_path: &Path) -> Result<Listener, String> {
+        Err("Unix sockets are not supported on this platform".to_string())
+    }
+
+    pub async fn serve(
+        listener: Listener,
+        _path: &Path,
+        _app: Router,
+        _stop: watch::Receiver<bool>,
+    ) {
+        match listener {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Routes in [`api_routes`] with no matching operation in the OpenAPI document.
+    fn undocumented_routes() -> Vec<String> {
+        let doc = ApiDoc::openapi();
+        api_routes()
+            .into_iter()
+            .filter(|(method, path, _)| {
+                // axum writes `/:param`, OpenAPI `/{param}`
+                let openapi_path = path
+                    .split('/')
+                    .map(|segment| match segment.strip_prefix(':') {
+                        Some(param) => format!("{{{}}}", param),
+                        None => segment.to_string(),
+                    })
+                    .collect::<Vec<_>>()
+                    .join("/");
+                let item = doc.paths.paths.get(&openapi_path);
+                let operation = item.and_then(|item| match *method {
+                    "get" => item.get.as_ref(),
+                    "post" => item.post.as_ref(),
+                    "patch" => item.patch.as_ref(),
+                    "delete" => item.delete.as_ref(),
+                    _ => None,
+                });
+                operation.is_none()
+            })
+            .map(|(method, path, _)| format!("{} {}", method.to_uppercase(), path))
+            .collect()
+    }
+
+    #[test]
+    fn every_route_is_documented() {
+        let missing = undocumented_routes();
+        assert!(
+            missing.is_empty(),
+            "Routes missing from ApiDoc: {:?}",
+            missing
+        );
+    }
+
+    #[test]
+    fn shared_responses_are_expanded() {
+        let doc = ApiDoc::openapi();
+        let item = &doc.paths.paths["/seek-to"];
+        let responses = &item.post.as_ref().unwrap().responses.responses;
+        for status in ["200", "400", "415", "422", "503"] {
+            assert!(responses.contains_key(status), "missing {}", status);
+        }
+    }
+}
@@ -0,0 +1,379 @@
+// src-tauri/src/http_server/error.rs
+
+//! Single JSON error shape for the API plus extractors that reject with it.
+
+use axum::{
+    async_trait,
+    extract::{
+        rejection::{JsonRejection, PathRejection},
+        FromRequest, FromRequestParts, Request,
+    },
+    http::{header, request::Parts, StatusCode},
+    response::{IntoResponse, Response},
+    Json,
+};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+use utoipa::ToSchema;
+
+/// Body of every non-2xx response: `{ code, message, details? }`.
+#[derive(Debug, Serialize, ToSchema)]
+pub struct ApiError {
+    #[serde(skip)]
+    status: StatusCode,
+    /// Stable, machine-readable error code, e.g. `player_timeout`.
+    #[schema(example = "player_timeout")]
+    code: String,
+    message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[schema(value_type = Option<Object>)]
+    details: Option<Value>,
+}
+
+impl ApiError {
+    pub fn new(status: StatusCode, code: &str, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            code: code.to_string(),
+            message: message.into(),
+            details: None,
+        }
+    }
+
+    pub fn with_details(mut self, details: Value) -> Self {
+        self.details = Some(details);
+        self
+    }
+
+    /// The player did not answer a live-data request.
+    pub fn player_timeout(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::GATEWAY_TIMEOUT, "player_timeout", message)
+    }
+
+    /// The player page or a Rust subsystem is not ready yet.
+    pub fn unavailable(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::SERVICE_UNAVAILABLE, "unavailable", message)
+    }
+
+    /// Some fields are missing, have the wrong type or are out of range;
+    /// `fields` maps each (by its JSON path) to a message.
+    pub fn validation(fields: Value) -> Self {
+        Self::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "validation_failed",
+            "One or more fields are invalid",
+        )
+        .with_details(serde_json::json!({ "fields": fields }))
+    }
+
+    pub fn not_found(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::NOT_FOUND, "not_found", message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (self.status, Json(self)).into_response()
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for ApiError {
+    fn from(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let message = error.inner().to_string();
+        let path = error.path().to_string();
+        // serde reports a missing field on the object that lacks it
+        let missing = message
+            .strip_prefix("missing field `")
+            .and_then(|m| m.strip_suffix('`'));
+        let field = match missing {
+            Some(missing) if path == "." => missing.to_string(),
+            Some(missing) => format!("{}.{}", path, missing),
+            None => path,
+        };
+        Self::validation(serde_json::json!({ field: message }))
+    }
+}
+
+impl From<JsonRejection> for ApiError {
+    fn from(rejection: JsonRejection) -> Self {
+        let (code, message) = match &rejection {
+            JsonRejection::JsonDataError(_) => (
+                "invalid_body",
+                "Request body does not match the expected fields",
+            ),
+            JsonRejection::JsonSyntaxError(_) => {
+                ("malformed_json", "Request body is not valid JSON")
+            }
+            JsonRejection::MissingJsonContentType(_) => (
+                "unsupported_media_type",
+                "Expected a request with `Content-Type: application/json`",
+            ),
+            _ => ("invalid_body", "Could not read the request body"),
+        };
+        Self::new(rejection.status(), code, message)
+            .with_details(serde_json::json!({ "reason": rejection.body_text() }))
+    }
+}
+
+/// axum answers a known path with an unsupported method with an empty 405;
+/// this gives it the usual error body and keeps the `Allow` header.
+pub async fn method_not_allowed(response: Response) -> Response {
+    if response.status() != StatusCode::METHOD_NOT_ALLOWED {
+        return response;
+    }
+    let allow = response.headers().get(header::ALLOW).cloned();
+    let mut json = ApiError::new(
+        StatusCode::METHOD_NOT_ALLOWED,
+        "method_not_allowed",
+        "Method not allowed for this route",
+    )
+    .into_response();
+    if let Some(allow) = allow {
+        json.headers_mut().insert(header::ALLOW, allow);
+    }
+    json
+}
+
+impl From<PathRejection> for ApiError {
+    fn from(rejection: PathRejection) -> Self {
+        Self::new(rejection.status(), "invalid_path", "Invalid path parameter")
+            .with_details(serde_json::json!({ "reason": rejection.body_text() }))
+    }
+}
+
+/// `Json` extractor whose rejections are [`ApiError`]s instead of plain text.
+/// Bodies that do not fit `T` are reported per field, like [`ApiError::validation`].
+pub struct ApiJson<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ApiJson<T>
+where
+    T: DeserializeOwned,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        // Going through `Value` keeps axum's content-type and syntax checks
+        // while serde_path_to_error records which field failed
+        let Json(value) = Json::<Value>::from_request(req, state).await?;
+        Ok(Self(serde_path_to_error::deserialize(value)?))
+    }
+}
+
+/// `Path` extractor whose rejections are [`ApiError`]s instead of plain text.
+pub struct ApiPath<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequestParts<S> for ApiPath<T>
+where
+    T: DeserializeOwned + Send,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        let axum::extract::Path(value) =
+            axum::extract::Path::<T>::from_request_parts(parts, state).await?;
+        Ok(Self(value))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bridge::AppState;
+    use axum::body::Body;
+    use axum::http::Method;
+    use axum::Router;
+    use http_body_util::BodyExt;
+    use tower::ServiceExt;
+
+    fn app() -> Router {
+        // No AppHandle: the player page is never reachable
+        Router::new()
+            .nest("/api/v1", super::super::api_router())
+            .with_state(AppState::default())
+    }
+
+    async fn send(method: Method, uri: &str, content_type: Option<&str>, body: Body) -> Response {
+        let mut request = axum::http::Request::builder().method(method).uri(uri);
+        if let Some(content_type) = content_type {
+            request = request.header(header::CONTENT_TYPE, content_type);
+        }
+        app().oneshot(request.body(body).unwrap()).await.unwrap()
+    }
+
+    async fn post_json(uri: &str, body: &str) -> Response {
+        send(
+            Method::POST,
+            uri,
+            Some("application/json"),
+            Body::from(body.to_string()),
+        )
+        .await
+    }
+
+    /// Checks the status, the content type and the `{ code, message, details? }` shape.
+    async fn assert_error(response: Response, status: StatusCode, code: &str) -> Value {
+        assert_eq!(response.status(), status);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let body: Value = serde_json::from_slice(&bytes).unwrap();
+        let fields = body.as_object().unwrap();
+        assert!(fields
+            .keys()
+            .all(|k| ["code", "message", "details"].contains(&k.as_str())));
+        assert_eq!(body["code"], code);
+        assert!(!body["message"].as_str().unwrap().is_empty());
+        body
+    }
+
+    #[tokio::test]
+    async fn syntax_error_is_malformed_json() {
+        let response = post_json("/api/v1/seek-to", r#"{"seconds":"#).await;
+        let body = assert_error(response, StatusCode::BAD_REQUEST, "malformed_json").await;
+        assert!(body["details"]["reason"].is_string());
+    }
+
+    /// Data errors and range checks share the `details.fields` shape.
+    async fn field_error(uri: &str, body: &str, field: &str) -> String {
+        let response = post_json(uri, body).await;
+        let body = assert_error(
+            response,
+            StatusCode::UNPROCESSABLE_ENTITY,
+            "validation_failed",
+        )
+        .await;
+        let fields = body["details"]["fields"].as_object().unwrap();
+        assert_eq!(fields.len(), 1, "{:?}", fields);
+        fields[field].as_str().unwrap().to_string()
+    }
+
+    #[tokio::test]
+    async fn wrong_type_is_a_field_error() {
+        let message = field_error("/api/v1/seek-to", r#"{"seconds":"ten"}"#, "seconds").await;
+        assert!(message.starts_with("invalid type"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn missing_field_is_a_field_error() {
+        let message = field_error("/api/v1/volume", "{}", "volume").await;
+        assert_eq!(message, "missing field `volume`");
+    }
+
+    #[tokio::test]
+    async fn unknown_enum_value_is_a_field_error() {
+        let message = field_error(
+            "/api/v1/queue",
+            r#"{"videoId":"dQw4w9WgXcQ","insertPosition":"SOMEWHERE"}"#,
+            "insertPosition",
+        )
+        .await;
+        assert!(message.contains("INSERT_AT_END"), "{}", message);
+    }
+
+    #[tokio::test]
+    async fn range_violation_is_a_field_error() {
+        let message = field_error("/api/v1/volume", r#"{"volume":150}"#, "volume").await;
+        assert_eq!(message, "must be between 0 and 100");
+    }
+
+    #[tokio::test]
+    async fn index_payloads_report_fields_too() {
+        field_error(
+            "/api/v1/queue/move",
+            r#"{"fromIndex":-1,"toIndex":2}"#,
+            "fromIndex",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn missing_content_type_is_unsupported_media_type() {
+        let response = send(
+            Method::POST,
+            "/api/v1/seek-to",
+            None,
+            Body::from(r#"{"seconds":10}"#),
+        )
+        .await;
+        assert_error(
+            response,
+            StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            "unsupported_media_type",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn other_rejections_fall_back_to_invalid_body() {
+        // Past axum's 2 MB body limit the rejection is neither a syntax nor a data error
+        let body = format!(r#"{{"query":"{}"}}"#, "a".repeat(3 * 1024 * 1024));
+        let response = post_json("/api/v1/search", &body).await;
+        assert_error(response, StatusCode::PAYLOAD_TOO_LARGE, "invalid_body").await;
+    }
+
+    #[tokio::test]
+    async fn bad_path_parameter_is_invalid_path() {
+        let response = send(Method::DELETE, "/api/v1/queue/first", None, Body::empty()).await;
+        assert_error(response, StatusCode::BAD_REQUEST, "invalid_path").await;
+    }
+
+    #[tokio::test]
+    async fn unknown_route_is_not_found() {
+        let response = send(Method::GET, "/api/v1/nope", None, Body::empty()).await;
+        assert_error(response, StatusCode::NOT_FOUND, "not_found").await;
+    }
+
+    #[tokio::test]
+    async fn wrong_method_is_method_not_allowed() {
+        let response = send(Method::DELETE, "/api/v1/song", None, Body::empty()).await;
+        assert!(response.headers().contains_key(header::ALLOW));
+        assert_error(
+            response,
+            StatusCode::METHOD_NOT_ALLOWED,
+            "method_not_allowed",
+        )
+        .await;
+    }
+
+    #[tokio::test]
+    async fn get_without_player_is_player_timeout() {
+        let response = send(Method::GET, "/api/v1/song", None, Body::empty()).await;
+        assert_error(response, StatusCode::GATEWAY_TIMEOUT, "player_timeout").await;
+    }
+
+    #[tokio::test]
+    async fn player_error_answer_is_bad_gateway() {
+        let answer = serde_json::json!({ "error": "No video element" });
+        let response = super::super::player_answer(answer)
+            .unwrap_err()
+            .into_response();
+        let body = assert_error(response, StatusCode::BAD_GATEWAY, "player_error").await;
+        assert_eq!(body["message"], "No video element");
+    }
+
+    #[tokio::test]
+    async fn command_without_player_is_unavailable() {
+        let response = send(Method::POST, "/api/v1/next", None, Body::empty()).await;
+        assert_error(response, StatusCode::SERVICE_UNAVAILABLE, "unavailable").await;
+    }
+
+    #[tokio::test]
+    async fn subsystem_without_app_is_unavailable() {
+        let response = send(Method::GET, "/api/v1/health", None, Body::empty()).await;
+        assert_error(response, StatusCode::SERVICE_UNAVAILABLE, "unavailable").await;
+    }
+
+    #[tokio::test]
+    async fn unknown_script_is_not_found() {
+        let response = post_json(
+            "/api/v1/scripts/enabled",
+            r#"{"id":"nope","enabled":false}"#,
+        )
+        .await;
+        assert_error(response, StatusCode::NOT_FOUND, "not_found").await;
+    }
+}
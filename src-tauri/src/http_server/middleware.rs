@@ -0,0 +1,391 @@
+// src-tauri/src/http_server/middleware.rs
+
+//! Per-client rate limiting and the JSON-lines access log for the API router.
+
+use super::error::ApiError;
+use axum::{
+    extract::{ConnectInfo, Request, State},
+    http::{header, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashMap;
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::sync::mpsc::{self, SyncSender, TrySendError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+const ACCESS_LOG_FILE: &str = "api-access.log";
+const MAX_LOG_BYTES: u64 = 1024 * 1024;
+/// Rotated files kept next to the live one (`api-access.log.1` ... `.3`).
+const KEPT_LOG_FILES: usize = 3;
+/// Entries waiting for the writer thread; more are dropped rather than stall requests.
+const LOG_QUEUE: usize = 1024;
+/// Buckets idle for this long are full again and can be dropped.
+const BUCKET_IDLE: Duration = Duration::from_secs(600);
+const MAX_TRACKED_BUCKETS: usize = 1024;
+
+/// Token bucket settings for one route class.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RateLimit {
+    /// Tokens refilled per second.
+    pub per_second: f64,
+    /// Bucket size, i.e. how many requests may arrive at once.
+    pub burst: u32,
+}
+
+/// Limits applied per client address; reads are `GET` routes, everything else is a command.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RateLimits {
+    pub enabled: bool,
+    pub reads: RateLimit,
+    pub commands: RateLimit,
+}
+
+impl Default for RateLimits {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            reads: RateLimit {
+                per_second: 20.0,
+                burst: 40,
+            },
+            commands: RateLimit {
+                per_second: 2.0,
+                burst: 5,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum RouteClass {
+    Read,
+    Command,
+}
+
+impl RouteClass {
+    fn of(method: &Method) -> Self {
+        match *method {
+            Method::GET | Method::HEAD | Method::OPTIONS => Self::Read,
+            _ => Self::Command,
+        }
+    }
+}
+
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// Shared state of both middlewares, built once per server start.
+pub struct Middleware {
+    limits: RateLimits,
+    buckets: Mutex<HashMap<(String, RouteClass), Bucket>>,
+    log: Option<AccessLog>,
+}
+
+impl Middleware {
+    pub fn new(limits: RateLimits, log_dir: Option<PathBuf>) -> Arc<Self> {
+        let log = log_dir.and_then(|dir| match AccessLog::open(dir) {
+            Ok(log) => Some(log),
+            Err(e) => {
+                eprintln!("⚠️ API access log disabled: {}", e);
+                None
+            }
+        });
+        Arc::new(Self {
+            limits,
+            buckets: Mutex::new(HashMap::new()),
+            log,
+        })
+    }
+
+    /// Takes one token at `now`; on failure returns how long until the next one.
+    fn take_token(&self, client: &str, class: RouteClass, now: Instant) -> Result<(), Duration> {
+        let limit = match class {
+            RouteClass::Read => self.limits.reads,
+            RouteClass::Command => self.limits.commands,
+        };
+        let capacity = f64::from(limit.burst.max(1));
+
+        let mut buckets = self.buckets.lock().unwrap();
+        if buckets.len() > MAX_TRACKED_BUCKETS {
+            buckets.retain(|_, b| now.duration_since(b.updated) < BUCKET_IDLE);
+        }
+        let bucket = buckets
+            .entry((client.to_string(), class))
+            .or_insert(Bucket {
+                tokens: capacity,
+                updated: now,
+            });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * limit.per_second).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        // A zero or negative refill rate never frees a token
+        Err(
+            Duration::try_from_secs_f64((1.0 - bucket.tokens) / limit.per_second)
+                .unwrap_or(Duration::MAX),
+        )
+    }
+}
+
+/// Client key for limits and logs: the peer IP on TCP, `unix` on the socket.
+fn client_of(req: &Request) -> String {
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map(|ConnectInfo(addr)| addr.ip().to_string())
+        .unwrap_or_else(|| "unix".to_string())
+}
+
+pub async fn rate_limit(
+    State(middleware): State<Arc<Middleware>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    if !middleware.limits.enabled {
+        return next.run(req).await;
+    }
+    let client = client_of(&req);
+    let class = RouteClass::of(req.method());
+    match middleware.take_token(&client, class, Instant::now()) {
+        Ok(()) => next.run(req).await,
+        Err(wait) => {
+            let retry_after = wait.as_secs().saturating_add(1).min(3600);
+            let mut response = ApiError::new(
+                StatusCode::TOO_MANY_REQUESTS,
+                "rate_limited",
+                format!("Too many requests, retry in {}s", retry_after),
+            )
+            .into_response();
+            response
+                .headers_mut()
+                .insert(header::RETRY_AFTER, HeaderValue::from(retry_after));
+            response
+        }
+    }
+}
+
+pub async fn access_log(
+    State(middleware): State<Arc<Middleware>>,
+    req: Request,
+    next: Next,
+) -> Response {
+    let Some(log) = &middleware.log else {
+        return next.run(req).await;
+    };
+    let client = client_of(&req);
+    let method = req.method().to_string();
+    let path = req.uri().path().to_string();
+    let started = Instant::now();
+
+    let response = next.run(req).await;
+
+    let entry = json!({
+        "time": chrono::Utc::now().to_rfc3339(),
+        "client": client,
+        "method": method,
+        "path": path,
+        "status": response.status().as_u16(),
+        "latency_ms": started.elapsed().as_secs_f64() * 1000.0,
+    });
+    log.send(entry.to_string());
+    response
+}
+
+/// Hands log lines to a writer thread so requests never wait on disk I/O.
+/// The thread ends once the server, and with it the sender, is dropped.
+struct AccessLog {
+    sender: SyncSender<String>,
+}
+
+impl AccessLog {
+    fn open(dir: PathBuf) -> std::io::Result<Self> {
+        let mut file = LogFile::open(dir.join(ACCESS_LOG_FILE), MAX_LOG_BYTES)?;
+        let (sender, receiver) = mpsc::sync_channel::<String>(LOG_QUEUE);
+        std::thread::Builder::new()
+            .name("api-access-log".to_string())
+            .spawn(move || {
+                for line in receiver {
+                    if let Err(e) = file.append(&line) {
+                        eprintln!("⚠️ API access log write failed: {}", e);
+                    }
+                }
+            })?;
+        Ok(Self { sender })
+    }
+
+    fn send(&self, line: String) {
+        if let Err(TrySendError::Full(_)) = self.sender.try_send(line) {
+            eprintln!("⚠️ API access log is behind, entry dropped");
+        }
+    }
+}
+
+/// `api-access.log`, rotated to `.1`..`.N` once it passes `max_bytes`.
+struct LogFile {
+    path: PathBuf,
+    file: File,
+    max_bytes: u64,
+}
+
+impl LogFile {
+    fn open(path: PathBuf, max_bytes: u64) -> std::io::Result<Self> {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)?;
+        }
+        let file = OpenOptions::new().create(true).append(true).open(&path)?;
+        Ok(Self {
+            path,
+            file,
+            max_bytes,
+        })
+    }
+
+    fn append(&mut self, line: &str) -> std::io::Result<()> {
+        if self.file.metadata()?.len() >= self.max_bytes {
+            self.rotate()?;
+            self.file = OpenOptions::new()
+                .create(true)
+                .append(true)
+                .open(&self.path)?;
+        }
+        writeln!(self.file, "{}", line)
+    }
+
+    fn rotate(&self) -> std::io::Result<()> {
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", self.path.display(), n));
+        let _ = fs::remove_file(rotated(KEPT_LOG_FILES));
+        for n in (1..KEPT_LOG_FILES).rev() {
+            let _ = fs::rename(rotated(n), rotated(n + 1));
+        }
+        fs::rename(&self.path, rotated(1))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::{body::Body, routing::post, Router};
+    use tower::ServiceExt;
+
+    fn limiter(per_second: f64, burst: u32) -> Arc<Middleware> {
+        let limit = RateLimit { per_second, burst };
+        Middleware::new(
+            RateLimits {
+                enabled: true,
+                reads: limit,
+                commands: limit,
+            },
+            None,
+        )
+    }
+
+    #[test]
+    fn burst_is_exhausted_then_refilled() {
+        let mw = limiter(2.0, 3);
+        let start = Instant::now();
+        for _ in 0..3 {
+            assert_eq!(mw.take_token("1.2.3.4", RouteClass::Command, start), Ok(()));
+        }
+        let wait = mw
+            .take_token("1.2.3.4", RouteClass::Command, start)
+            .unwrap_err();
+        assert_eq!(wait, Duration::from_millis(500));
+
+        // Half a second at 2/s gives back exactly one token
+        let later = start + Duration::from_millis(500);
+        assert_eq!(mw.take_token("1.2.3.4", RouteClass::Command, later), Ok(()));
+        assert!(mw
+            .take_token("1.2.3.4", RouteClass::Command, later)
+            .is_err());
+    }
+
+    #[test]
+    fn refill_stops_at_the_burst_size() {
+        let mw = limiter(10.0, 2);
+        let start = Instant::now();
+        assert!(mw.take_token("a", RouteClass::Read, start).is_ok());
+        let later = start + Duration::from_secs(60);
+        assert!(mw.take_token("a", RouteClass::Read, later).is_ok());
+        assert!(mw.take_token("a", RouteClass::Read, later).is_ok());
+        assert!(mw.take_token("a", RouteClass::Read, later).is_err());
+    }
+
+    #[test]
+    fn clients_and_route_classes_have_separate_buckets() {
+        let mw = limiter(1.0, 1);
+        let now = Instant::now();
+        assert!(mw.take_token("a", RouteClass::Command, now).is_ok());
+        assert!(mw.take_token("a", RouteClass::Command, now).is_err());
+        assert!(mw.take_token("a", RouteClass::Read, now).is_ok());
+        assert!(mw.take_token("b", RouteClass::Command, now).is_ok());
+    }
+
+    #[test]
+    fn zero_rate_never_refills() {
+        let mw = limiter(0.0, 1);
+        let now = Instant::now();
+        assert!(mw.take_token("a", RouteClass::Command, now).is_ok());
+        assert_eq!(
+            mw.take_token("a", RouteClass::Command, now + Duration::from_secs(3600)),
+            Err(Duration::MAX)
+        );
+    }
+
+    #[tokio::test]
+    async fn exhausted_bucket_answers_429_with_retry_after() {
+        let app = Router::new().route("/next", post(|| async { "ok" })).layer(
+            axum::middleware::from_fn_with_state(limiter(0.4, 1), rate_limit),
+        );
+        let request = || {
+            axum::http::Request::post("/next")
+                .body(Body::empty())
+                .unwrap()
+        };
+
+        let first = app.clone().oneshot(request()).await.unwrap();
+        assert_eq!(first.status(), StatusCode::OK);
+        let second = app.oneshot(request()).await.unwrap();
+        assert_eq!(second.status(), StatusCode::TOO_MANY_REQUESTS);
+        assert_eq!(second.headers()[header::RETRY_AFTER], "3");
+        assert_eq!(second.headers()[header::CONTENT_TYPE], "application/json");
+    }
+
+    #[test]
+    fn log_rotates_at_the_size_limit() {
+        let dir = std::env::temp_dir().join(format!("yt-hear-access-log-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let path = dir.join(ACCESS_LOG_FILE);
+        let rotated = |n: usize| PathBuf::from(format!("{}.{}", path.display(), n));
+
+        // Each line is 10 bytes with its newline, so every second append rotates
+        let mut log = LogFile::open(path.clone(), 20).unwrap();
+        for n in 0..10 {
+            log.append(&format!("entry-{:03}", n)).unwrap();
+        }
+
+        assert_eq!(fs::read_to_string(&path).unwrap(), "entry-008\nentry-009\n");
+        assert_eq!(
+            fs::read_to_string(rotated(1)).unwrap(),
+            "entry-006\nentry-007\n"
+        );
+        assert_eq!(
+            fs::read_to_string(rotated(KEPT_LOG_FILES)).unwrap(),
+            "entry-002\nentry-003\n"
+        );
+        assert!(!rotated(KEPT_LOG_FILES + 1).exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
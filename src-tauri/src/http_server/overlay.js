@@ -0,0 +1,48 @@
+// src-tauri/src/http_server/overlay.js
+// Actualiza #art, #title, #artist y #progress con los eventos del reproductor.
+
+(function () {
+    const byId = (id) => document.getElementById(id);
+    let duration = 0;
+
+    function setProgress(currentTime) {
+        const progress = byId("progress");
+        if (!progress || !duration) return;
+        const ratio = Math.min(Math.max(currentTime / duration, 0), 1);
+        progress.style.width = (ratio * 100).toFixed(2) + "%";
+    }
+
+    function onSong(song) {
+        const overlay = byId("overlay");
+        if (!song || !song.title) {
+            if (overlay) overlay.classList.add("empty");
+            return;
+        }
+        if (overlay) {
+            overlay.classList.remove("empty");
+            overlay.classList.toggle("paused", !!song.isPaused);
+        }
+        if (byId("title")) byId("title").textContent = song.title;
+        if (byId("artist")) byId("artist").textContent = song.artist || "";
+        const art = byId("art");
+        if (art && song.imageSrc && art.src !== song.imageSrc) art.src = song.imageSrc;
+        duration = song.duration || duration;
+        setProgress(song.currentTime || 0);
+    }
+
+    function connect() {
+        const events = new EventSource("/overlay/events");
+        events.addEventListener("song-info", (e) => onSong(JSON.parse(e.data)));
+        events.addEventListener("time-update", (e) => {
+            const time = JSON.parse(e.data);
+            if (time.duration) duration = time.duration;
+            setProgress(time.currentTime || 0);
+        });
+        // EventSource reconnects by itself unless the server closed the stream for good
+        events.onerror = () => {
+            if (events.readyState === EventSource.CLOSED) setTimeout(connect, 5000);
+        };
+    }
+
+    document.addEventListener("DOMContentLoaded", connect);
+})();
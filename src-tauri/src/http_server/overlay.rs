@@ -0,0 +1,226 @@
+// src-tauri/src/http_server/overlay.rs
+
+//! Now-playing overlay for OBS browser sources at `/overlay`.
+//!
+//! `layout`, `font`, `size`, `color`, `background` and `accent` query
+//! parameters theme the built-in page. `?template=<name>` loads
+//! `<app_data_dir>/overlay/<name>.html` and/or `<name>.css` instead; without
+//! it, `overlay.html` / `overlay.css` there replace or extend the built-in
+//! look. Templates put `{{head}}` in `<head>` (styles and the live script)
+//! and may use `{{layout}}` as a body class.
+
+use super::error::ApiError;
+use crate::bridge::{topics, AppState};
+use axum::{
+    extract::{Query, State},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        Html,
+    },
+    routing::get,
+    Router,
+};
+use futures_util::stream::{self, Stream, StreamExt};
+use serde::Deserialize;
+use std::convert::Infallible;
+use std::path::PathBuf;
+use tauri::Manager;
+use tokio::sync::{broadcast::error::RecvError, watch};
+
+const DEFAULT_TEMPLATE: &str = include_str!("./overlay.html");
+const SCRIPT: &str = include_str!("./overlay.js");
+const OVERLAY_DIR: &str = "overlay";
+const DEFAULT_TEMPLATE_NAME: &str = "overlay";
+const LAYOUTS: &[&str] = &["horizontal", "vertical", "minimal"];
+/// Telemetry topics forwarded to the page; the SSE event name is the topic.
+const TOPICS: &[&str] = &[topics::SONG_INFO, topics::TIME_UPDATE];
+
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct OverlayQuery {
+    layout: Option<String>,
+    font: Option<String>,
+    /// Base font size in px; everything else scales with it.
+    size: Option<String>,
+    color: Option<String>,
+    background: Option<String>,
+    accent: Option<String>,
+    template: Option<String>,
+}
+
+/// `/overlay` and its event stream. `stop` ends open streams so the
+/// server can shut down gracefully.
+pub fn routes(stop: watch::Receiver<bool>) -> Router<AppState> {
+    Router::new().route("/overlay", get(page)).route(
+        "/overlay/events",
+        get(move |state: State<AppState>| events(state, stop.clone())),
+    )
+}
+
+async fn page(
+    State(state): State<AppState>,
+    Query(query): Query<OverlayQuery>,
+) -> Result<Html<String>, ApiError> {
+    let name = match query.template.as_deref() {
+        Some(name) if is_template_name(name) => name,
+        Some(_) => return Err(ApiError::not_found("Invalid overlay template name")),
+        None => DEFAULT_TEMPLATE_NAME,
+    };
+    let dir = overlay_dir(&state).await;
+    let read = |ext: &str| {
+        let path = dir.as_ref().map(|d| d.join(format!("{}.{}", name, ext)));
+        async move {
+            match path {
+                Some(path) => tokio::fs::read_to_string(path).await.ok(),
+                None => None,
+            }
+        }
+    };
+    let custom_html = read("html").await;
+    let custom_css = read("css").await;
+    if query.template.is_some() && custom_html.is_none() && custom_css.is_none() {
+        return Err(ApiError::not_found(format!(
+            "No overlay template named '{}'",
+            name
+        )));
+    }
+
+    let layout = query
+        .layout
+        .as_deref()
+        .filter(|l| LAYOUTS.contains(l))
+        .unwrap_or(LAYOUTS[0]);
+    let head = format!(
+        "<style>:root {{ --font: {font}; --size: {size}px; --color: {color}; \
+         --background: {background}; --accent: {accent}; }}</style>\n\
+         <style>{custom_css}</style>\n<script>{script}</script>",
+        font = query
+            .font
+            .as_deref()
+            .filter(|f| is_font_name(f))
+            .map(|f| format!("\"{}\", sans-serif", f))
+            .unwrap_or_else(|| "sans-serif".to_string()),
+        size = query
+            .size
+            .and_then(|s| s.parse::<u32>().ok())
+            .filter(|s| (8..=200).contains(s))
+            .unwrap_or(24),
+        color = css_color(query.color.as_deref(), "#ffffff"),
+        background = css_color(query.background.as_deref(), "transparent"),
+        accent = css_color(query.accent.as_deref(), "#ff0033"),
+        custom_css = custom_css.unwrap_or_default().replace("</style", ""),
+        script = SCRIPT,
+    );
+
+    let html = custom_html.as_deref().unwrap_or(DEFAULT_TEMPLATE);
+    Ok(Html(
+        html.replace("{{head}}", &head)
+            .replace("{{layout}}", &format!("layout-{}", layout)),
+    ))
+}
+
+/// Player events as Server-Sent Events, starting with the current song.
+async fn events(
+    State(state): State<AppState>,
+    stop: watch::Receiver<bool>,
+) -> Sse<impl Stream<Item = Result<Event, Infallible>>> {
+    let telemetry = state.telemetry.subscribe();
+    let current = state
+        .request_live_data("get-song-info", 2000)
+        .await
+        .ok()
+        .filter(|song| song.get("error").is_none())
+        .map(|song| {
+            Ok(Event::default()
+                .event(topics::SONG_INFO)
+                .data(song.to_string()))
+        });
+
+    let live = stream::unfold((telemetry, stop), |(mut telemetry, mut stop)| async move {
+        loop {
+            tokio::select! {
+                received = telemetry.recv() => match received {
+                    Ok((topic, payload)) if TOPICS.contains(&topic.as_str()) => {
+                        let event = Event::default().event(topic).data(payload.to_string());
+                        return Some((Ok(event), (telemetry, stop)));
+                    }
+                    Ok(_) | Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                },
+                _ = stop.changed() => return None,
+            }
+        }
+    });
+
+    Sse::new(stream::iter(current).chain(live)).keep_alive(KeepAlive::default())
+}
+
+async fn overlay_dir(state: &AppState) -> Option<PathBuf> {
+    let handle = state.app_handle.lock().await;
+    let dir = handle.as_ref()?.path().app_data_dir().ok()?;
+    Some(dir.join(OVERLAY_DIR))
+}
+
+fn is_template_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+fn is_font_name(font: &str) -> bool {
+    !font.is_empty()
+        && font
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == ' ' || c == '-')
+}
+
+/// Colors come from the URL; anything that could close the declaration is dropped.
+/// `#` must be sent as `%23`, or given without it (`color=ff0033`).
+fn css_color(value: Option<&str>, default: &str) -> String {
+    match value {
+        Some(v) if !v.is_empty() && v.chars().all(|c| c.is_ascii_hexdigit()) => {
+            format!("#{}", v)
+        }
+        Some(v)
+            if !v.is_empty()
+                && v.chars()
+                    .all(|c| c.is_ascii_alphanumeric() || "#(),.% ".contains(c)) =>
+        {
+            v.to_string()
+        }
+        _ => default.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn script_listens_for_every_forwarded_topic() {
+        for topic in TOPICS {
+            let listener = format!("addEventListener(\"{}\"", topic);
+            assert!(SCRIPT.contains(&listener), "overlay.js ignores {}", topic);
+        }
+    }
+
+    #[test]
+    fn script_reads_fields_the_page_sends() {
+        for (variable, topic) in [("song.", topics::SONG_INFO), ("time.", topics::TIME_UPDATE)] {
+            let sample = topics::sample(topic);
+            for (at, _) in SCRIPT.match_indices(variable) {
+                let field: String = SCRIPT[at + variable.len()..]
+                    .chars()
+                    .take_while(|c| c.is_ascii_alphanumeric())
+                    .collect();
+                assert!(
+                    sample.get(&field).is_some(),
+                    "the page sends no {} in {}",
+                    field,
+                    topic
+                );
+            }
+        }
+    }
+}
@@ -0,0 +1,117 @@
+// src-tauri/src/http_server/validation.rs
+
+//! Payload checks at the API boundary. Anything the player would silently
+//! misbehave on is rejected with a 422 and one message per field.
+
+use super::error::{ApiError, ApiJson};
+use crate::bridge::AppState;
+use axum::{
+    async_trait,
+    extract::{FromRequest, Request},
+};
+use serde::de::DeserializeOwned;
+use serde_json::{Map, Value};
+use std::time::{Duration, Instant};
+
+/// How long a queue length read from the player is trusted for index checks.
+const QUEUE_CACHE_TTL: Duration = Duration::from_secs(5);
+const VIDEO_ID_LEN: usize = 11;
+
+/// Field name → message, reported as `details.fields`.
+#[derive(Default)]
+pub struct FieldErrors(Map<String, Value>);
+
+impl FieldErrors {
+    pub fn add(&mut self, field: &str, message: impl Into<String>) {
+        self.0
+            .entry(field.to_string())
+            .or_insert_with(|| Value::String(message.into()));
+    }
+
+    pub fn check(&mut self, ok: bool, field: &str, message: &str) {
+        if !ok {
+            self.add(field, message);
+        }
+    }
+
+    pub fn into_result(self) -> Result<(), ApiError> {
+        if self.0.is_empty() {
+            Ok(())
+        } else {
+            Err(ApiError::validation(Value::Object(self.0)))
+        }
+    }
+}
+
+/// Checks that only need the payload itself.
+pub trait Validate {
+    fn validate(&self, errors: &mut FieldErrors);
+}
+
+/// [`ApiJson`] that also runs [`Validate`] before the handler sees the payload.
+pub struct ValidJson<T>(pub T);
+
+#[async_trait]
+impl<S, T> FromRequest<S> for ValidJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(req: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let ApiJson(value) = ApiJson::<T>::from_request(req, state).await?;
+        let mut errors = FieldErrors::default();
+        value.validate(&mut errors);
+        errors.into_result()?;
+        Ok(Self(value))
+    }
+}
+
+pub fn is_video_id(id: &str) -> bool {
+    id.len() == VIDEO_ID_LEN
+        && id
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+}
+
+/// Checks each `(field, index)` against the queue length. When the player
+/// cannot be asked, the indices are let through and the player ignores them.
+pub async fn check_queue_indices(
+    state: &AppState,
+    indices: &[(&str, usize)],
+) -> Result<(), ApiError> {
+    let Some(len) = queue_len(state).await else {
+        return Ok(());
+    };
+    let mut errors = FieldErrors::default();
+    for (field, index) in indices {
+        if *index >= len {
+            errors.add(field, format!("must be below the queue length ({})", len));
+        }
+    }
+    errors.into_result()
+}
+
+/// Remembers the length of a queue the player just returned.
+pub async fn cache_queue(state: &AppState, queue: &Value) {
+    if let Some(items) = queue["items"].as_array() {
+        *state.queue_len_cache.lock().await = Some((Instant::now(), items.len()));
+    }
+}
+
+/// Forgets the cached length after a command that changes the queue.
+pub async fn invalidate_queue(state: &AppState) {
+    *state.queue_len_cache.lock().await = None;
+}
+
+async fn queue_len(state: &AppState) -> Option<usize> {
+    if let Some((at, len)) = *state.queue_len_cache.lock().await {
+        if at.elapsed() < QUEUE_CACHE_TTL {
+            return Some(len);
+        }
+    }
+    let queue = state.request_live_data("get-queue", 2000).await.ok()?;
+    cache_queue(state, &queue).await;
+    queue["items"].as_array().map(Vec::len)
+}
@@ -0,0 +1,740 @@
+// src-tauri/src/lyrics.rs
+
+//! Lyrics for the current song from LRCLIB-style providers.
+//!
+//! Every `song-info` change looks the track up (disk cache first, then each
+//! provider in order). `ytm:lyrics-line` is emitted only when a `time-update`
+//! lands on another synced line (or before the first one), never per tick, so
+//! listeners keep the last line until the next event. `GET /api/v1/lyrics`
+//! serves the same data.
+
+use crate::bridge::topics;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{mpsc, Mutex};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use tauri::{AppHandle, Emitter, Listener, Manager};
+use utoipa::ToSchema;
+
+const CACHE_DIR: &str = "lyrics";
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+/// "Not found" answers are cached too, but retried after a day.
+const NOT_FOUND_TTL_SECS: u64 = 24 * 60 * 60;
+/// A track whose providers all failed is not looked up again before this, so
+/// API polling does not hammer them while they are down.
+const PROVIDER_FAILURE_TTL: Duration = Duration::from_secs(60);
+/// Search results whose length differs more than this are another recording.
+const DURATION_TOLERANCE_SECS: f64 = 3.0;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LyricsSettings {
+    pub enabled: bool,
+    /// Base URLs of LRCLIB-compatible servers, tried in order.
+    pub providers: Vec<String>,
+}
+
+impl Default for LyricsSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            providers: vec!["https://lrclib.net".to_string()],
+        }
+    }
+}
+
+/// One synced line; `time` is in seconds from the start of the song.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct LyricLine {
+    pub time: f64,
+    pub text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct Lyrics {
+    /// Base URL of the provider that answered.
+    pub source: String,
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub duration: f64,
+    pub instrumental: bool,
+    pub plain: Option<String>,
+    /// Empty when the provider only has plain lyrics.
+    pub synced: Vec<LyricLine>,
+}
+
+/// What a lookup is keyed on, taken from `getCurrentSong` in the page.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct TrackQuery {
+    pub title: String,
+    pub artist: String,
+    pub album: String,
+    pub duration: u64,
+}
+
+impl TrackQuery {
+    /// `None` while nothing is playing.
+    pub fn from_song(song: &Value) -> Option<Self> {
+        let song: topics::SongInfo = topics::parse(song)?;
+        let query = Self {
+            title: song.title.trim().to_string(),
+            artist: song.artist.trim().to_string(),
+            album: song.album.trim().to_string(),
+            duration: song.duration,
+        };
+        (!query.title.is_empty()).then_some(query)
+    }
+
+    fn same_track(&self, other: &Self) -> bool {
+        self.title == other.title && self.artist == other.artist
+    }
+
+    /// Cache file name; stable across runs (FNV-1a of the lowercased fields).
+    fn cache_key(&self) -> String {
+        let mut hash: u64 = 0xcbf29ce484222325;
+        let key = format!("{}\n{}\n{}", self.title, self.artist, self.album).to_lowercase();
+        for byte in key.bytes() {
+            hash ^= u64::from(byte);
+            hash = hash.wrapping_mul(0x100000001b3);
+        }
+        format!("{:016x}.json", hash)
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    fetched_at: u64,
+    lyrics: Option<Lyrics>,
+}
+
+/// Lyrics of the song being played, as seen by the event listeners.
+struct Current {
+    query: TrackQuery,
+    lyrics: Option<Lyrics>,
+    line: Option<usize>,
+}
+
+/// Last lookup where every provider failed: `(cache key, when, error)`.
+type Failure = (String, Instant, String);
+
+#[derive(Default)]
+pub struct LyricsStore {
+    settings: Mutex<LyricsSettings>,
+    cache_dir: Mutex<Option<PathBuf>>,
+    current: Mutex<Option<Current>>,
+    failure: Mutex<Option<Failure>>,
+}
+
+impl LyricsStore {
+    pub fn is_enabled(&self) -> bool {
+        self.settings.lock().unwrap().enabled
+    }
+
+    /// Cached lyrics or a provider lookup. `Ok(None)` means no provider has them.
+    /// Blocking: performs HTTP requests.
+    pub fn lookup(&self, query: &TrackQuery) -> Result<Option<Lyrics>, String> {
+        {
+            let current = self.current.lock().unwrap();
+            if let Some(current) = current.as_ref().filter(|c| c.query.same_track(query)) {
+                if current.lyrics.is_some() {
+                    return Ok(current.lyrics.clone());
+                }
+            }
+        }
+        let cache_path = self
+            .cache_dir
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|dir| dir.join(query.cache_key()));
+        if let Some(entry) = cache_path.as_deref().and_then(read_cache) {
+            if entry.lyrics.is_some() || now_secs() < entry.fetched_at + NOT_FOUND_TTL_SECS {
+                return Ok(entry.lyrics);
+            }
+        }
+
+        let key = query.cache_key();
+        if let Some((_, _, error)) = self
+            .failure
+            .lock()
+            .unwrap()
+            .as_ref()
+            .filter(|(failed, at, _)| *failed == key && at.elapsed() < PROVIDER_FAILURE_TTL)
+        {
+            return Err(error.clone());
+        }
+
+        let providers = self.settings.lock().unwrap().providers.clone();
+        let mut last_error = None;
+        let mut answered = false;
+        for base in &providers {
+            match fetch(base, query) {
+                Ok(Some(lyrics)) => {
+                    write_cache(cache_path.as_deref(), Some(&lyrics));
+                    return Ok(Some(lyrics));
+                }
+                Ok(None) => answered = true,
+                Err(e) => {
+                    eprintln!("⚠️ Lyrics provider {} failed: {}", base, e);
+                    last_error = Some(e);
+                }
+            }
+        }
+        match last_error {
+            // Only remember "not found" when no provider was unreachable
+            Some(e) if !answered => {
+                *self.failure.lock().unwrap() = Some((key, Instant::now(), e.clone()));
+                Err(e)
+            }
+            _ => {
+                if last_error.is_none() {
+                    write_cache(cache_path.as_deref(), None);
+                }
+                Ok(None)
+            }
+        }
+    }
+
+    /// Stores a finished lookup unless the song changed meanwhile.
+    fn set_current(&self, query: &TrackQuery, lyrics: Option<Lyrics>) -> bool {
+        let mut current = self.current.lock().unwrap();
+        match current.as_mut() {
+            Some(current) if current.query.same_track(query) => {
+                current.lyrics = lyrics;
+                current.line = None;
+                true
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the new line when playback moved to another synced line;
+    /// `None` while the line stays the same.
+    fn advance(&self, seconds: f64) -> Option<(Option<usize>, Option<LyricLine>)> {
+        let mut current = self.current.lock().unwrap();
+        let current = current.as_mut()?;
+        let synced = &current.lyrics.as_ref()?.synced;
+        let line = synced.partition_point(|l| l.time <= seconds).checked_sub(1);
+        if line == current.line {
+            return None;
+        }
+        current.line = line;
+        Some((line, line.map(|i| synced[i].clone())))
+    }
+}
+
+/// Loads the settings and starts following the player.
+pub fn start(app: &AppHandle) {
+    let store = app.state::<LyricsStore>();
+    let settings = app
+        .state::<crate::preferences::PreferencesStore>()
+        .get()
+        .lyrics;
+    let enabled = settings.enabled;
+    *store.settings.lock().unwrap() = settings;
+    match app.path().app_cache_dir() {
+        Ok(dir) => *store.cache_dir.lock().unwrap() = Some(dir.join(CACHE_DIR)),
+        Err(e) => eprintln!("⚠️ Lyrics cache disabled: {}", e),
+    }
+    if !enabled {
+        return;
+    }
+
+    // Lookups block on HTTP, so they run one at a time on a worker thread
+    let (tx, rx) = mpsc::channel::<TrackQuery>();
+    let listener_app = app.clone();
+    app.listen_any(topics::event(topics::SONG_INFO), move |event| {
+        let song = serde_json::from_str::<Value>(event.payload()).unwrap_or(Value::Null);
+        let Some(query) = TrackQuery::from_song(&song) else {
+            return;
+        };
+        let store = listener_app.state::<LyricsStore>();
+        let mut current = store.current.lock().unwrap();
+        if current.as_ref().is_some_and(|c| c.query.same_track(&query)) {
+            return;
+        }
+        *current = Some(Current {
+            query: query.clone(),
+            lyrics: None,
+            line: None,
+        });
+        let _ = tx.send(query);
+    });
+
+    let worker_app = app.clone();
+    std::thread::spawn(move || {
+        for query in rx {
+            let store = worker_app.state::<LyricsStore>();
+            let lyrics = match store.lookup(&query) {
+                Ok(lyrics) => lyrics,
+                Err(e) => {
+                    eprintln!("❌ Lyrics lookup failed: {}", e);
+                    None
+                }
+            };
+            let found = lyrics.is_some();
+            let synced = lyrics.as_ref().is_some_and(|l| !l.synced.is_empty());
+            if store.set_current(&query, lyrics) {
+                let _ = worker_app.emit(
+                    "ytm:lyrics",
+                    json!({
+                        "title": query.title,
+                        "artist": query.artist,
+                        "found": found,
+                        "synced": synced,
+                    }),
+                );
+            }
+        }
+    });
+
+    let time_app = app.clone();
+    app.listen_any(topics::event(topics::TIME_UPDATE), move |event| {
+        let Ok(time) = serde_json::from_str::<Value>(event.payload()) else {
+            return;
+        };
+        let Some(seconds) = playback_position(&time) else {
+            return;
+        };
+        if let Some((index, line)) = time_app.state::<LyricsStore>().advance(seconds) {
+            let _ = time_app.emit(
+                "ytm:lyrics-line",
+                json!({
+                    "index": index,
+                    "time": line.as_ref().map(|l| l.time),
+                    "text": line.map(|l| l.text),
+                }),
+            );
+        }
+    });
+}
+
+/// Seconds into the song from a `time-update` payload.
+fn playback_position(time: &Value) -> Option<f64> {
+    topics::parse::<topics::TimeUpdate>(time)
+        .map(|time| time.current_time)
+        .filter(|s| s.is_finite())
+}
+
+/// LRCLIB record as returned by `/api/get` and `/api/search`.
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct ProviderRecord {
+    #[serde(default)]
+    track_name: String,
+    #[serde(default)]
+    artist_name: String,
+    #[serde(default)]
+    album_name: Option<String>,
+    #[serde(default)]
+    duration: f64,
+    #[serde(default)]
+    instrumental: bool,
+    plain_lyrics: Option<String>,
+    synced_lyrics: Option<String>,
+}
+
+impl ProviderRecord {
+    fn into_lyrics(self, source: &str) -> Lyrics {
+        Lyrics {
+            source: source.to_string(),
+            title: self.track_name,
+            artist: self.artist_name,
+            album: self.album_name.unwrap_or_default(),
+            duration: self.duration,
+            instrumental: self.instrumental,
+            synced: self
+                .synced_lyrics
+                .as_deref()
+                .map(parse_lrc)
+                .unwrap_or_default(),
+            plain: self.plain_lyrics,
+        }
+    }
+}
+
+/// Exact match through `/api/get` when album and duration are known, then `/api/search`.
+fn fetch(base: &str, query: &TrackQuery) -> Result<Option<Lyrics>, String> {
+    let base = base.trim_end_matches('/');
+    let agent = ureq::AgentBuilder::new()
+        .timeout(Duration::from_secs(REQUEST_TIMEOUT_SECS))
+        .user_agent(concat!(
+            env!("CARGO_PKG_NAME"),
+            "/",
+            env!("CARGO_PKG_VERSION")
+        ))
+        .build();
+
+    if !query.album.is_empty() && query.duration > 0 {
+        let response = agent
+            .get(&format!("{}/api/get", base))
+            .query("track_name", &query.title)
+            .query("artist_name", &query.artist)
+            .query("album_name", &query.album)
+            .query("duration", &query.duration.to_string())
+            .call();
+        match response {
+            Ok(response) => {
+                let record: ProviderRecord = response.into_json().map_err(|e| e.to_string())?;
+                return Ok(Some(record.into_lyrics(base)));
+            }
+            Err(ureq::Error::Status(404, _)) => {}
+            Err(e) => return Err(e.to_string()),
+        }
+    }
+
+    let records: Vec<ProviderRecord> = agent
+        .get(&format!("{}/api/search", base))
+        .query("track_name", &query.title)
+        .query("artist_name", &query.artist)
+        .call()
+        .map_err(|e| e.to_string())?
+        .into_json()
+        .map_err(|e| e.to_string())?;
+    let close_enough = |r: &ProviderRecord| {
+        query.duration == 0 || (r.duration - query.duration as f64).abs() <= DURATION_TOLERANCE_SECS
+    };
+    let has_lyrics = |r: &ProviderRecord| r.instrumental || r.plain_lyrics.is_some();
+    let best = records
+        .iter()
+        .position(|r| close_enough(r) && r.synced_lyrics.is_some())
+        .or_else(|| {
+            records
+                .iter()
+                .position(|r| close_enough(r) && has_lyrics(r))
+        });
+    Ok(best.and_then(|i| records.into_iter().nth(i).map(|r| r.into_lyrics(base))))
+}
+
+/// Parses LRC text: `[mm:ss.xx]` stamps (several per line allowed) and the
+/// `[offset:±ms]` tag; other tags such as `[ar:...]` are skipped.
+pub fn parse_lrc(text: &str) -> Vec<LyricLine> {
+    let mut offset = 0.0;
+    let mut lines = Vec::new();
+    for raw in text.lines() {
+        let mut rest = raw.trim();
+        let mut times = Vec::new();
+        while rest.starts_with('[') {
+            let Some(end) = rest.find(']') else {
+                break;
+            };
+            let tag = &rest[1..end];
+            rest = &rest[end + 1..];
+            if let Some(time) = parse_timestamp(tag) {
+                times.push(time);
+            } else if let Some(ms) = tag.strip_prefix("offset:") {
+                offset = ms.trim().parse::<f64>().unwrap_or(0.0) / 1000.0;
+            }
+        }
+        let text = rest.trim();
+        lines.extend(times.into_iter().map(|time| LyricLine {
+            time,
+            text: text.to_string(),
+        }));
+    }
+    // A positive offset shows the lyrics earlier
+    for line in &mut lines {
+        line.time = (line.time - offset).max(0.0);
+    }
+    lines.sort_by(|a, b| a.time.total_cmp(&b.time));
+    lines
+}
+
+/// `mm:ss`, `mm:ss.xx` or `mm:ss:xx` to seconds.
+fn parse_timestamp(tag: &str) -> Option<f64> {
+    let (minutes, seconds) = tag.split_once(':')?;
+    let minutes: u32 = minutes.trim().parse().ok()?;
+    let seconds: f64 = seconds.trim().replacen(':', ".", 1).parse().ok()?;
+    (seconds.is_finite() && seconds >= 0.0).then_some(f64::from(minutes) * 60.0 + seconds)
+}
+
+fn read_cache(path: &Path) -> Option<CacheEntry> {
+    let raw = fs::read_to_string(path).ok()?;
+    serde_json::from_str(&raw).ok()
+}
+
+fn write_cache(path: Option<&Path>, lyrics: Option<&Lyrics>) {
+    let Some(path) = path else {
+        return;
+    };
+    let entry = json!({ "fetched_at": now_secs(), "lyrics": lyrics });
+    let result = path
+        .parent()
+        .map_or(Ok(()), fs::create_dir_all)
+        .and_then(|_| fs::write(path, entry.to_string()));
+    if let Err(e) = result {
+        eprintln!("⚠️ Lyrics cache write failed: {}", e);
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    fn line(time: f64, text: &str) -> LyricLine {
+        LyricLine {
+            time,
+            text: text.to_string(),
+        }
+    }
+
+    #[test]
+    fn reads_the_fields_the_page_sends() {
+        let song = topics::sample_with(topics::SONG_INFO, json!({ "title": " Song " }));
+        assert_eq!(TrackQuery::from_song(&song), Some(query("Album", 215)));
+        assert_eq!(TrackQuery::from_song(&Value::Null), None);
+
+        let time = topics::sample(topics::TIME_UPDATE);
+        assert_eq!(playback_position(&time), Some(12.5));
+        assert_eq!(playback_position(&json!({ "currentTime": "12" })), None);
+    }
+
+    #[test]
+    fn parses_centiseconds_and_whole_seconds() {
+        assert_eq!(
+            parse_lrc("[00:12.50]First\n[01:02]Second\n[02:03:25]Third"),
+            vec![
+                line(12.5, "First"),
+                line(62.0, "Second"),
+                line(123.25, "Third")
+            ]
+        );
+    }
+
+    #[test]
+    fn repeats_lines_with_several_stamps() {
+        assert_eq!(
+            parse_lrc("[00:30.00][00:10.00]Chorus\n[00:20.00]Verse"),
+            vec![
+                line(10.0, "Chorus"),
+                line(20.0, "Verse"),
+                line(30.0, "Chorus")
+            ]
+        );
+    }
+
+    #[test]
+    fn applies_the_offset_tag() {
+        // Positive offsets show lyrics earlier, negative later; never before 0
+        assert_eq!(
+            parse_lrc("[offset:+500]\n[00:00.20]Intro\n[00:10.00]Line"),
+            vec![line(0.0, "Intro"), line(9.5, "Line")]
+        );
+        assert_eq!(
+            parse_lrc("[offset:-1000]\n[00:10.00]Line"),
+            vec![line(11.0, "Line")]
+        );
+    }
+
+    #[test]
+    fn skips_metadata_and_unstamped_lines() {
+        assert_eq!(
+            parse_lrc("[ar:Artist]\n[ti:Title]\nno stamp\n[00:01.00]  Spaced  \n[xx:yy]Bad"),
+            vec![line(1.0, "Spaced")]
+        );
+    }
+
+    /// Serves canned `(path prefix, status, body)` answers; returns the base URL.
+    fn stub(routes: Vec<(&'static str, u16, String)>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let mut reader = BufReader::new(&stream);
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+                let mut header = String::new();
+                while reader.read_line(&mut header).is_ok_and(|n| n > 2) {
+                    header.clear();
+                }
+                let path = request_line.split(' ').nth(1).unwrap_or_default();
+                let (status, body) = routes
+                    .iter()
+                    .find(|(prefix, _, _)| path.starts_with(prefix))
+                    .map(|(_, status, body)| (*status, body.as_str()))
+                    .unwrap_or((404, "{}"));
+                let _ = write!(
+                    &stream,
+                    "HTTP/1.1 {} Stub\r\nContent-Type: application/json\r\n\
+                     Content-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+            }
+        });
+        base
+    }
+
+    fn query(album: &str, duration: u64) -> TrackQuery {
+        TrackQuery {
+            title: "Song".to_string(),
+            artist: "Artist".to_string(),
+            album: album.to_string(),
+            duration,
+        }
+    }
+
+    fn record(duration: f64, synced: Option<&str>, instrumental: bool) -> Value {
+        json!({
+            "trackName": "Song",
+            "artistName": "Artist",
+            "albumName": "Album",
+            "duration": duration,
+            "instrumental": instrumental,
+            "plainLyrics": if instrumental { None } else { Some("Hello") },
+            "syncedLyrics": synced,
+        })
+    }
+
+    fn store(providers: Vec<String>) -> LyricsStore {
+        let store = LyricsStore::default();
+        *store.settings.lock().unwrap() = LyricsSettings {
+            enabled: true,
+            providers,
+        };
+        store
+    }
+
+    #[test]
+    fn provider_hit_through_exact_match() {
+        let base = stub(vec![(
+            "/api/get",
+            200,
+            record(200.0, Some("[00:01.00]Hello"), false).to_string(),
+        )]);
+        let lyrics = fetch(&base, &query("Album", 200)).unwrap().unwrap();
+        assert_eq!(lyrics.source, base);
+        assert_eq!(lyrics.plain.as_deref(), Some("Hello"));
+        assert_eq!(lyrics.synced, vec![line(1.0, "Hello")]);
+    }
+
+    #[test]
+    fn provider_miss_falls_back_to_search() {
+        // /api/get answers 404, the search has only another recording
+        let base = stub(vec![(
+            "/api/search",
+            200,
+            json!([record(260.0, Some("[00:01.00]Hello"), false)]).to_string(),
+        )]);
+        assert!(fetch(&base, &query("Album", 200)).unwrap().is_none());
+    }
+
+    #[test]
+    fn search_prefers_synced_lyrics() {
+        let base = stub(vec![(
+            "/api/search",
+            200,
+            json!([
+                record(201.0, None, false),
+                record(199.0, Some("[00:02.00]Hello"), false)
+            ])
+            .to_string(),
+        )]);
+        let lyrics = fetch(&base, &query("", 200)).unwrap().unwrap();
+        assert_eq!(lyrics.synced, vec![line(2.0, "Hello")]);
+    }
+
+    #[test]
+    fn provider_instrumental() {
+        let base = stub(vec![(
+            "/api/search",
+            200,
+            json!([record(200.0, None, true)]).to_string(),
+        )]);
+        let lyrics = fetch(&base, &query("", 200)).unwrap().unwrap();
+        assert!(lyrics.instrumental);
+        assert!(lyrics.plain.is_none());
+        assert!(lyrics.synced.is_empty());
+    }
+
+    #[test]
+    fn lookup_skips_failing_providers() {
+        let broken = stub(vec![("/api", 500, "{}".to_string())]);
+        let working = stub(vec![(
+            "/api/search",
+            200,
+            json!([record(200.0, Some("[00:01.00]Hello"), false)]).to_string(),
+        )]);
+        let store = store(vec![broken.clone(), working.clone()]);
+        let lyrics = store.lookup(&query("", 200)).unwrap().unwrap();
+        assert_eq!(lyrics.source, working);
+
+        // Every provider failing is an error, not "no lyrics"
+        let store = self::store(vec![broken]);
+        assert!(store.lookup(&query("", 200)).is_err());
+    }
+
+    #[test]
+    fn provider_failures_are_remembered_briefly() {
+        let broken = stub(vec![("/api", 500, "{}".to_string())]);
+        let working = stub(vec![(
+            "/api/search",
+            200,
+            json!([record(200.0, Some("[00:01.00]Hello"), false)]).to_string(),
+        )]);
+        let store = store(vec![broken]);
+        assert!(store.lookup(&query("", 200)).is_err());
+
+        // The providers are not asked again right away
+        store.settings.lock().unwrap().providers = vec![working];
+        assert!(store.lookup(&query("", 200)).is_err());
+        // Another track is looked up
+        assert!(store.lookup(&query("Album", 200)).unwrap().is_some());
+
+        if let Some((_, at, _)) = store.failure.lock().unwrap().as_mut() {
+            *at = Instant::now().checked_sub(PROVIDER_FAILURE_TTL).unwrap();
+        }
+        assert!(store.lookup(&query("", 200)).unwrap().is_some());
+    }
+
+    #[test]
+    fn lookup_reports_a_miss() {
+        let empty = stub(vec![("/api/search", 200, "[]".to_string())]);
+        assert!(store(vec![empty])
+            .lookup(&query("", 200))
+            .unwrap()
+            .is_none());
+    }
+
+    #[test]
+    fn lyrics_line_only_changes_between_lines() {
+        let store = LyricsStore::default();
+        let query = query("", 200);
+        *store.current.lock().unwrap() = Some(Current {
+            query: query.clone(),
+            lyrics: None,
+            line: None,
+        });
+        let lyrics = Lyrics {
+            source: String::new(),
+            title: query.title.clone(),
+            artist: query.artist.clone(),
+            album: String::new(),
+            duration: 200.0,
+            instrumental: false,
+            plain: None,
+            synced: vec![line(1.0, "One"), line(5.0, "Two")],
+        };
+        assert!(store.set_current(&query, Some(lyrics)));
+
+        assert_eq!(store.advance(0.5), None);
+        assert_eq!(store.advance(1.0), Some((Some(0), Some(line(1.0, "One")))));
+        assert_eq!(store.advance(3.0), None);
+        assert_eq!(store.advance(6.0), Some((Some(1), Some(line(5.0, "Two")))));
+        assert_eq!(store.advance(0.0), Some((None, None)));
+    }
+}
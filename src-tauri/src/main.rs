@@ -5,23 +5,60 @@
 )]
 
 mod adblock_plugin;
+mod adblock_stats;
 mod bridge;
+mod cli;
 mod http_server;
+mod lyrics;
+mod mqtt;
+mod now_playing;
+mod preferences;
 mod scripts;
+mod shutdown;
+mod single_instance;
 mod tray;
 mod window;
-use scripts::ScriptId;
+use preferences::PreferencesStore;
+use scripts::health::{self, ScriptHealth};
+use scripts::injector::LoadTracker;
+use scripts::plugins::PluginRegistry;
+use scripts::settings::ScriptSettings;
+use shutdown::ShutdownCoordinator;
 use std::sync::Arc;
 use tauri::{AppHandle, Manager};
+use window::WindowStateStore;
 
 const MAIN_WINDOW_LABEL: &str = "main";
 
 fn main() {
+    let launch_args: Vec<String> = std::env::args().skip(1).collect();
+    if launch_args.first().map(String::as_str) == Some("ctl") {
+        std::process::exit(cli::run(&launch_args[1..]));
+    }
+    let instance = match single_instance::acquire(&launch_args) {
+        Ok(single_instance::Instance::Forwarded) => return,
+        #[cfg(unix)]
+        Ok(single_instance::Instance::Unresponsive) => return,
+        Ok(instance) => Some(instance),
+        Err(e) => {
+            eprintln!("⚠️ Single-instance guard error: {}", e);
+            None
+        }
+    };
+
     let app_state = Arc::new(bridge::AppState::default());
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
         .plugin(adblock_plugin::init())
         .manage(app_state.clone())
+        .manage(PluginRegistry::default())
+        .manage(ScriptSettings::default())
+        .manage(ScriptHealth::default())
+        .manage(LoadTracker::default())
+        .manage(WindowStateStore::default())
+        .manage(PreferencesStore::default())
+        .manage(lyrics::LyricsStore::default())
+        .manage(ShutdownCoordinator::default())
         .invoke_handler(tauri::generate_handler![
             window_commands::minimize,
             window_commands::toggle_maximize,
@@ -29,6 +66,8 @@ fn main() {
             bridge::push_telemetry,
             bridge::resolve_request,
             cmd_toggle_server,
+            scripts::plugins::report_plugin_error,
+            scripts::health::report_script_health,
         ])
         .setup(move |app| {
             let handle = app.handle().clone();
@@ -44,18 +83,46 @@ fn main() {
                 *state_for_async.app_handle.lock().await = Some(handle_for_async);
             });
 
+            if let Err(e) = handle.state::<ScriptSettings>().load(&handle) {
+                eprintln!("⚠️ Script settings error: {}", e);
+            }
+            if let Err(e) = scripts::plugins::discover_in_app_data(&handle) {
+                eprintln!("⚠️ Plugin discovery error: {}", e);
+            }
+            if let Err(e) = handle.state::<WindowStateStore>().load(&handle) {
+                eprintln!("⚠️ Window state error: {}", e);
+            }
+            let window_state_handle = handle.clone();
+            handle
+                .state::<ShutdownCoordinator>()
+                .register("window-state", move || {
+                    window_state_handle.state::<WindowStateStore>().save();
+                    Ok(())
+                });
+            if let Err(e) = handle.state::<PreferencesStore>().load(&handle) {
+                eprintln!("⚠️ Preferences error: {}", e);
+            }
+            preferences::sync_autostart(&handle);
+            mqtt::start(&handle);
+            now_playing::start(&handle);
+            lyrics::start(&handle);
+            window::listen_for_session_end(handle.clone());
+
             setup_main_window(app)?;
             setup_tray(&handle)?;
+
+            if let Some(instance) = instance {
+                single_instance::serve(instance, handle.clone());
+            }
+            single_instance::apply(
+                &handle,
+                &single_instance::ForwardedArgs::parse(&launch_args),
+            );
             Ok(())
         })
-        .on_page_load(|window, _| {
+        .on_page_load(|window, payload| {
             if window.label() == MAIN_WINDOW_LABEL {
-                println!("💉 Injecting Scripts...");
-                for script_id in ScriptId::ALL_IN_ORDER {
-                    if let Err(e) = window.eval(script_id.content()) {
-                        eprintln!("❌ Script Error [{:?}]: {}", script_id, e);
-                    }
-                }
+                scripts::injector::on_page_load(window, payload);
             }
         })
         .on_window_event(|window, event| {
@@ -84,10 +151,33 @@ fn main() {
 }
 
 fn setup_main_window(app: &mut tauri::App) -> Result<(), Box<dyn std::error::Error>> {
-    if let Some(window) = app.get_webview_window(MAIN_WINDOW_LABEL) {
-        #[cfg(debug_assertions)]
-        window.open_devtools();
+    // Created here instead of from tauri.conf.json ("create": false) so it can
+    // carry initialization scripts, which run before any page script
+    let config = app
+        .config()
+        .app
+        .windows
+        .iter()
+        .find(|w| w.label == MAIN_WINDOW_LABEL)
+        .cloned()
+        .ok_or("Missing main window config")?;
+    // Built hidden so the saved geometry is applied before the first paint
+    let window = tauri::WebviewWindowBuilder::from_config(app.handle(), &config)?
+        .initialization_script(health::PRELUDE)
+        .visible(false)
+        .build()?;
+    window::restore_geometry(&window);
+    if app.state::<PreferencesStore>().get().start_minimized {
+        println!("🫥 Starting minimized to tray");
+    } else if config.visible {
+        window.show()?;
     }
+
+    #[cfg(debug_assertions)]
+    window.open_devtools();
+
+    #[cfg(debug_assertions)]
+    scripts::hot_reload::watch(app.handle().clone());
     Ok(())
 }
 
@@ -98,11 +188,9 @@ fn setup_tray(handle: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
     })
 }
 
-pub fn cleanup_and_exit(_app: &AppHandle) {
-    std::thread::spawn(|| {
-        std::thread::sleep(std::time::Duration::from_millis(100));
-        std::process::exit(0);
-    });
+/// Runs the graceful shutdown sequence, which ends in `AppHandle::exit`.
+pub fn cleanup_and_exit(app: &AppHandle) {
+    shutdown::request(app);
 }
 
 mod window_commands {
@@ -131,6 +219,7 @@ mod window_commands {
 #[tauri::command]
 async fn cmd_toggle_server(
     state: tauri::State<'_, Arc<bridge::AppState>>,
+    preferences: tauri::State<'_, PreferencesStore>,
     port: Option<u16>,
 ) -> Result<String, String> {
     // FIX 4: Use .lock().await (no unwrap)
@@ -140,7 +229,14 @@ async fn cmd_toggle_server(
         // FIX 5: Add .await because stop_server is async
         http_server::stop_server(&state).await
     } else if let Some(p) = port {
-        http_server::start_server(p, state.inner().clone()).await
+        let prefs = preferences.get();
+        http_server::start_server(
+            p,
+            prefs.api_listen,
+            prefs.api_rate_limits,
+            state.inner().clone(),
+        )
+        .await
     } else {
         Err("Server stopped (no valid port provided).".to_string())
     }
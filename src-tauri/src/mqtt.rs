@@ -0,0 +1,648 @@
+// src-tauri/src/mqtt.rs
+
+//! Optional MQTT publisher for Home Assistant and other home automation.
+//!
+//! Player telemetry is published as retained topics under `<base_topic>/`,
+//! commands arrive on `<base_topic>/command` (argument-less actions) and a few
+//! `.../set` topics (actions that need a number) and are forwarded as
+//! `ytm:command` actions. Home Assistant discovery configs
+//! are published on every (re)connect.
+
+use crate::bridge;
+use crate::bridge::topics::{SongInfo, StateChange, TimeUpdate, VolumeChange};
+use rumqttc::{AsyncClient, Event, LastWill, MqttOptions, Packet, QoS};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter, Listener, Manager};
+
+const RECONNECT_DELAY_SECS: u64 = 5;
+/// How long shutdown waits for the event loop to send the disconnect.
+/// Shorter than the coordinator's hook timeout so failures are reported.
+const DISCONNECT_TIMEOUT: Duration = Duration::from_secs(2);
+const DEVICE_ID: &str = "yt_hear";
+/// At most one publish-failure line per interval while the broker is down.
+const PUBLISH_ERROR_LOG_INTERVAL: Duration = Duration::from_secs(30);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MqttSettings {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    pub client_id: String,
+    /// Prefix of every state and command topic.
+    pub base_topic: String,
+    /// Home Assistant discovery prefix; empty disables discovery.
+    pub discovery_prefix: String,
+}
+
+impl Default for MqttSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            host: "localhost".to_string(),
+            port: 1883,
+            username: None,
+            password: None,
+            client_id: "yt-hear".to_string(),
+            base_topic: "yt-hear".to_string(),
+            discovery_prefix: "homeassistant".to_string(),
+        }
+    }
+}
+
+/// Connects to the broker and starts publishing when MQTT is enabled in the preferences.
+pub fn start(app: &AppHandle) {
+    let settings = app
+        .state::<crate::preferences::PreferencesStore>()
+        .get()
+        .mqtt;
+    if !settings.enabled {
+        return;
+    }
+    println!(
+        "📡 MQTT: connecting to {}:{} as {}",
+        settings.host, settings.port, settings.client_id
+    );
+
+    let topics = Topics::new(&settings.base_topic);
+    let mut options = MqttOptions::new(&settings.client_id, &settings.host, settings.port);
+    options.set_keep_alive(Duration::from_secs(30));
+    options.set_last_will(LastWill::new(
+        &topics.availability,
+        "offline",
+        QoS::AtLeastOnce,
+        true,
+    ));
+    if let Some(username) = &settings.username {
+        options.set_credentials(username, settings.password.clone().unwrap_or_default());
+    }
+    let (client, mut eventloop) = AsyncClient::new(options, 64);
+
+    publish_telemetry(app, &client, &topics);
+
+    // The event loop signals once the disconnect has gone out to the broker
+    let (disconnected_tx, disconnected_rx) = tokio::sync::oneshot::channel::<()>();
+    let shutdown_client = client.clone();
+    let availability = topics.availability.clone();
+    app.state::<crate::shutdown::ShutdownCoordinator>()
+        .register("mqtt", move || {
+            tauri::async_runtime::block_on(async move {
+                // Requests are sent in order, so `offline` reaches the broker first
+                shutdown_client
+                    .publish(availability, QoS::AtLeastOnce, true, "offline")
+                    .await
+                    .map_err(|e| e.to_string())?;
+                shutdown_client
+                    .disconnect()
+                    .await
+                    .map_err(|e| e.to_string())?;
+                tokio::time::timeout(DISCONNECT_TIMEOUT, disconnected_rx)
+                    .await
+                    .map(|_| ())
+                    .map_err(|_| "broker connection did not close in time".to_string())
+            })
+        });
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match eventloop.poll().await {
+                Ok(Event::Incoming(Packet::ConnAck(_))) => {
+                    println!("✅ MQTT: connected");
+                    if let Err(e) = on_connect(&client, &topics, &settings).await {
+                        eprintln!("❌ MQTT: setup after connect failed: {}", e);
+                    }
+                }
+                Ok(Event::Incoming(Packet::Publish(publish))) => {
+                    let payload = String::from_utf8_lossy(&publish.payload);
+                    handle_command(&app, &topics, &publish.topic, payload.trim());
+                }
+                Ok(Event::Outgoing(rumqttc::Outgoing::Disconnect)) => break,
+                Ok(_) => {}
+                Err(e) => {
+                    eprintln!("⚠️ MQTT: {} (retrying in {}s)", e, RECONNECT_DELAY_SECS);
+                    tokio::time::sleep(Duration::from_secs(RECONNECT_DELAY_SECS)).await;
+                }
+            }
+        }
+        println!("🛑 MQTT: disconnected");
+        let _ = disconnected_tx.send(());
+    });
+}
+
+/// Every topic this module uses, derived from the base topic.
+#[derive(Clone)]
+struct Topics {
+    availability: String,
+    state: String,
+    track: String,
+    title: String,
+    artist: String,
+    album: String,
+    cover: String,
+    duration: String,
+    position: String,
+    volume: String,
+    volume_level: String,
+    muted: String,
+    shuffle: String,
+    repeat: String,
+    command: String,
+    volume_set: String,
+    volume_level_set: String,
+    seek_set: String,
+    go_back_set: String,
+    go_forward_set: String,
+}
+
+impl Topics {
+    fn new(base: &str) -> Self {
+        let base = base.trim_end_matches('/');
+        let topic = |name: &str| format!("{}/{}", base, name);
+        Self {
+            availability: topic("availability"),
+            state: topic("state"),
+            track: topic("track"),
+            title: topic("title"),
+            artist: topic("artist"),
+            album: topic("album"),
+            cover: topic("cover_url"),
+            duration: topic("duration"),
+            position: topic("position"),
+            volume: topic("volume"),
+            volume_level: topic("volume_level"),
+            muted: topic("muted"),
+            shuffle: topic("shuffle"),
+            repeat: topic("repeat"),
+            command: topic("command"),
+            volume_set: topic("volume/set"),
+            volume_level_set: topic("volume_level/set"),
+            seek_set: topic("seek/set"),
+            go_back_set: topic("go_back/set"),
+            go_forward_set: topic("go_forward/set"),
+        }
+    }
+}
+
+async fn on_connect(
+    client: &AsyncClient,
+    topics: &Topics,
+    settings: &MqttSettings,
+) -> Result<(), rumqttc::ClientError> {
+    for topic in [
+        &topics.command,
+        &topics.volume_set,
+        &topics.volume_level_set,
+        &topics.seek_set,
+        &topics.go_back_set,
+        &topics.go_forward_set,
+    ] {
+        client.subscribe(topic, QoS::AtLeastOnce).await?;
+    }
+    client
+        .publish(&topics.availability, QoS::AtLeastOnce, true, "online")
+        .await?;
+    if !settings.discovery_prefix.is_empty() {
+        for (topic, config) in discovery_configs(topics, &settings.discovery_prefix) {
+            client
+                .publish(topic, QoS::AtLeastOnce, true, config.to_string())
+                .await?;
+        }
+    }
+    Ok(())
+}
+
+/// Retained state topics fed from the player telemetry events.
+fn publish_telemetry(app: &AppHandle, client: &AsyncClient, topics: &Topics) {
+    let errors = Arc::new(Mutex::new(ErrorLog::default()));
+    let last_position = Arc::new(Mutex::new(None::<u64>));
+    for topic in [
+        bridge::topics::SONG_INFO,
+        bridge::topics::TIME_UPDATE,
+        bridge::topics::VOLUME_CHANGE,
+        bridge::topics::STATE_CHANGE,
+    ] {
+        let (client, t) = (client.clone(), topics.clone());
+        let (errors, last_position) = (errors.clone(), last_position.clone());
+        app.listen_any(bridge::topics::event(topic), move |event| {
+            let Ok(payload) = serde_json::from_str::<Value>(event.payload()) else {
+                return;
+            };
+            let messages =
+                telemetry_messages(&t, topic, &payload, &mut last_position.lock().unwrap());
+            for (mqtt_topic, retain, body) in messages {
+                let qos = if retain {
+                    QoS::AtLeastOnce
+                } else {
+                    QoS::AtMostOnce
+                };
+                if let Err(e) = client.try_publish(&mqtt_topic, qos, retain, body) {
+                    if let Some(suppressed) = errors.lock().unwrap().should_log(Instant::now()) {
+                        eprintln!(
+                            "⚠️ MQTT: publish to {} failed: {} ({} similar errors suppressed)",
+                            mqtt_topic, e, suppressed
+                        );
+                    }
+                }
+            }
+        });
+    }
+}
+
+/// Messages `(topic, retain, payload)` for one telemetry event from the page.
+/// `last_position` holds the last whole second sent: time-update fires several
+/// times per second, only whole seconds are published.
+fn telemetry_messages(
+    t: &Topics,
+    topic: &str,
+    payload: &Value,
+    last_position: &mut Option<u64>,
+) -> Vec<(String, bool, String)> {
+    let mut messages = Vec::new();
+    let mut retained = |topic: &str, payload: String| {
+        messages.push((topic.to_string(), true, payload));
+    };
+    match topic {
+        bridge::topics::SONG_INFO => {
+            let Some(song) = bridge::topics::parse::<SongInfo>(payload) else {
+                retained(&t.state, "idle".to_string());
+                return messages;
+            };
+            let state = if song.is_paused { "paused" } else { "playing" };
+            retained(&t.state, state.to_string());
+            retained(&t.title, song.title.clone());
+            retained(&t.artist, song.artist.clone());
+            retained(&t.album, song.album.clone());
+            retained(&t.cover, song.image_src.clone());
+            retained(&t.duration, song.duration.to_string());
+            retained(
+                &t.track,
+                json!({
+                    "title": song.title,
+                    "artist": song.artist,
+                    "album": song.album,
+                    "imageSrc": song.image_src,
+                    "duration": song.duration,
+                    "url": song.url,
+                })
+                .to_string(),
+            );
+        }
+        bridge::topics::TIME_UPDATE => {
+            let Some(time) = bridge::topics::parse::<TimeUpdate>(payload) else {
+                return messages;
+            };
+            let seconds = time.current_time.max(0.0) as u64;
+            if *last_position != Some(seconds) {
+                *last_position = Some(seconds);
+                messages.push((t.position.clone(), false, seconds.to_string()));
+            }
+        }
+        bridge::topics::VOLUME_CHANGE => {
+            if let Some(volume) = bridge::topics::parse::<VolumeChange>(payload) {
+                retained(&t.volume, volume.volume.to_string());
+                retained(
+                    &t.volume_level,
+                    format!("{:.2}", volume.volume as f64 / 100.0),
+                );
+                retained(&t.muted, on_off(volume.is_muted).to_string());
+            }
+        }
+        bridge::topics::STATE_CHANGE => {
+            if let Some(state) = bridge::topics::parse::<StateChange>(payload) {
+                retained(&t.shuffle, on_off(state.shuffle).to_string());
+                retained(&t.repeat, state.repeat);
+            }
+        }
+        _ => {}
+    }
+    messages
+}
+
+/// Throttles the publish-failure message: `try_publish` keeps failing for as
+/// long as the request queue is full (broker unreachable), once per event.
+#[derive(Default)]
+struct ErrorLog {
+    last: Option<Instant>,
+    suppressed: u64,
+}
+
+impl ErrorLog {
+    /// Returns the number of failures skipped since the last printed one when
+    /// this one should be printed.
+    fn should_log(&mut self, now: Instant) -> Option<u64> {
+        match self.last {
+            Some(last) if now.duration_since(last) < PUBLISH_ERROR_LOG_INTERVAL => {
+                self.suppressed += 1;
+                None
+            }
+            _ => {
+                self.last = Some(now);
+                Some(std::mem::take(&mut self.suppressed))
+            }
+        }
+    }
+}
+
+fn on_off(value: bool) -> &'static str {
+    if value {
+        "ON"
+    } else {
+        "OFF"
+    }
+}
+
+/// Forwards an incoming MQTT message as a `ytm:command` action.
+fn handle_command(app: &AppHandle, topics: &Topics, topic: &str, payload: &str) {
+    let Some(command) = command_for(topics, topic, payload) else {
+        eprintln!("⚠️ MQTT: ignoring {} = {:?}", topic, payload);
+        return;
+    };
+    if let Err(e) = app.emit("ytm:command", command) {
+        eprintln!("❌ MQTT: error emitting command: {}", e);
+    }
+}
+
+/// Maps a message onto a `ytm:command` action. The command topic only takes
+/// argument-less actions; the `.../set` topics need a number in range.
+fn command_for(topics: &Topics, topic: &str, payload: &str) -> Option<Value> {
+    let number = || payload.parse::<f64>().ok().filter(|n| n.is_finite());
+    if topic == topics.command {
+        crate::single_instance::FORWARDABLE_ACTIONS
+            .contains(&payload)
+            .then(|| json!({ "action": payload }))
+    } else if topic == topics.volume_set {
+        number()
+            .filter(|v| (0.0..=100.0).contains(v))
+            .map(|v| json!({ "action": "setVolume", "value": v }))
+    } else if topic == topics.volume_level_set {
+        number()
+            .filter(|v| (0.0..=1.0).contains(v))
+            .map(|v| json!({ "action": "setVolume", "value": v * 100.0 }))
+    } else if topic == topics.seek_set {
+        number()
+            .filter(|s| *s >= 0.0)
+            .map(|s| json!({ "action": "seek", "value": s }))
+    } else if topic == topics.go_back_set || topic == topics.go_forward_set {
+        let action = if topic == topics.go_back_set {
+            "goBack"
+        } else {
+            "goForward"
+        };
+        number()
+            .filter(|s| *s > 0.0)
+            .map(|s| json!({ "action": action, "value": s }))
+    } else {
+        None
+    }
+}
+
+/// Home Assistant discovery: a `media_player` for the `mqtt_media_player`
+/// custom integration (core HA has no MQTT media player), plus a sensor,
+/// buttons and a volume slider that work with the stock MQTT integration.
+fn discovery_configs(topics: &Topics, prefix: &str) -> Vec<(String, Value)> {
+    let device = json!({
+        "identifiers": [DEVICE_ID],
+        "name": "yt-hear",
+        "model": "YouTube Music desktop client",
+        "sw_version": env!("CARGO_PKG_VERSION"),
+    });
+    let config_topic = |component: &str, object: &str| {
+        format!("{}/{}/{}/{}/config", prefix, component, DEVICE_ID, object)
+    };
+    let entity = |object: &str, name: &str, extra: Value| {
+        let mut config = json!({
+            "name": name,
+            "unique_id": format!("{}_{}", DEVICE_ID, object),
+            "availability_topic": topics.availability,
+            "device": device,
+        });
+        if let (Some(config), Value::Object(extra)) = (config.as_object_mut(), extra) {
+            config.extend(extra);
+        }
+        config
+    };
+
+    let mut configs = vec![
+        (
+            config_topic("media_player", "player"),
+            entity(
+                "player",
+                "yt-hear",
+                json!({
+                    "state_state_topic": topics.state,
+                    "state_title_topic": topics.title,
+                    "state_artist_topic": topics.artist,
+                    "state_album_topic": topics.album,
+                    "state_duration_topic": topics.duration,
+                    "state_position_topic": topics.position,
+                    "state_volume_topic": topics.volume_level,
+                    "state_albumart_topic": topics.cover,
+                    "command_volume_topic": topics.volume_level_set,
+                    "command_play_topic": topics.command,
+                    "command_play_payload": "play",
+                    "command_pause_topic": topics.command,
+                    "command_pause_payload": "pause",
+                    "command_playpause_topic": topics.command,
+                    "command_playpause_payload": "playPause",
+                    "command_next_topic": topics.command,
+                    "command_next_payload": "next",
+                    "command_previous_topic": topics.command,
+                    "command_previous_payload": "previous",
+                }),
+            ),
+        ),
+        (
+            config_topic("sensor", "now_playing"),
+            entity(
+                "now_playing",
+                "Now Playing",
+                json!({
+                    "state_topic": topics.track,
+                    "value_template": "{{ value_json.artist }} - {{ value_json.title }}",
+                    "json_attributes_topic": topics.track,
+                    "icon": "mdi:music",
+                }),
+            ),
+        ),
+        (
+            config_topic("number", "volume"),
+            entity(
+                "volume",
+                "Volume",
+                json!({
+                    "state_topic": topics.volume,
+                    "command_topic": topics.volume_set,
+                    "min": 0,
+                    "max": 100,
+                    "step": 1,
+                    "icon": "mdi:volume-high",
+                }),
+            ),
+        ),
+    ];
+    for (action, name, icon) in [
+        ("playPause", "Play/Pause", "mdi:play-pause"),
+        ("next", "Next", "mdi:skip-next"),
+        ("previous", "Previous", "mdi:skip-previous"),
+        ("like", "Like", "mdi:thumb-up"),
+    ] {
+        let object = action.to_lowercase();
+        configs.push((
+            config_topic("button", &object),
+            entity(
+                &object,
+                name,
+                json!({
+                    "command_topic": topics.command,
+                    "payload_press": action,
+                    "icon": icon,
+                }),
+            ),
+        ));
+    }
+    configs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn command(topic: &str, payload: &str) -> Option<Value> {
+        command_for(&Topics::new("yt-hear/"), topic, payload)
+    }
+
+    #[test]
+    fn argument_less_actions_on_the_command_topic() {
+        assert_eq!(
+            command("yt-hear/command", "next"),
+            Some(json!({ "action": "next" }))
+        );
+        assert_eq!(command("yt-hear/command", "reboot"), None);
+    }
+
+    #[test]
+    fn actions_needing_a_value_are_rejected_on_the_command_topic() {
+        assert_eq!(command("yt-hear/command", "goBack"), None);
+        assert_eq!(command("yt-hear/command", "goForward"), None);
+        assert_eq!(command("yt-hear/command", "seek"), None);
+        assert_eq!(command("yt-hear/command", "setVolume"), None);
+    }
+
+    #[test]
+    fn seeking_relative_needs_a_positive_number() {
+        assert_eq!(
+            command("yt-hear/go_back/set", "15"),
+            Some(json!({ "action": "goBack", "value": 15.0 }))
+        );
+        assert_eq!(
+            command("yt-hear/go_forward/set", "30"),
+            Some(json!({ "action": "goForward", "value": 30.0 }))
+        );
+        for payload in ["", "0", "-5", "NaN", "ten"] {
+            assert_eq!(command("yt-hear/go_back/set", payload), None, "{}", payload);
+        }
+    }
+
+    fn messages(
+        topic: &str,
+        payload: Value,
+        last_position: &mut Option<u64>,
+    ) -> Vec<(String, bool, String)> {
+        telemetry_messages(&Topics::new("yt-hear"), topic, &payload, last_position)
+    }
+
+    #[test]
+    fn song_info_maps_onto_the_track_topics() {
+        let song =
+            bridge::topics::sample_with(bridge::topics::SONG_INFO, json!({ "isPaused": true }));
+        let sent = messages(bridge::topics::SONG_INFO, song, &mut None);
+        let value = |topic: &str| {
+            sent.iter()
+                .find(|(t, retain, _)| t == topic && *retain)
+                .map(|(_, _, payload)| payload.as_str())
+        };
+        assert_eq!(value("yt-hear/state"), Some("paused"));
+        assert_eq!(value("yt-hear/title"), Some("Song"));
+        assert_eq!(value("yt-hear/artist"), Some("Artist"));
+        assert_eq!(
+            value("yt-hear/cover_url"),
+            Some("https://lh3.googleusercontent.com/cover")
+        );
+        assert_eq!(value("yt-hear/duration"), Some("215"));
+        let track: Value = serde_json::from_str(value("yt-hear/track").unwrap()).unwrap();
+        assert_eq!(track["url"], "https://music.youtube.com/watch?v=abc");
+
+        let idle = messages(bridge::topics::SONG_INFO, Value::Null, &mut None);
+        assert_eq!(
+            idle,
+            [("yt-hear/state".to_string(), true, "idle".to_string())]
+        );
+    }
+
+    #[test]
+    fn time_update_publishes_each_whole_second_once() {
+        let mut last = None;
+        let mut position = |seconds: f64| {
+            let time = bridge::topics::sample_with(
+                bridge::topics::TIME_UPDATE,
+                json!({ "currentTime": seconds }),
+            );
+            messages(bridge::topics::TIME_UPDATE, time, &mut last)
+        };
+        assert_eq!(
+            position(3.2),
+            [("yt-hear/position".to_string(), false, "3".to_string())]
+        );
+        assert!(position(3.7).is_empty());
+        assert_eq!(position(4.01).len(), 1);
+    }
+
+    #[test]
+    fn volume_and_player_state_map_onto_their_topics() {
+        let volume = bridge::topics::sample(bridge::topics::VOLUME_CHANGE);
+        assert_eq!(
+            messages(bridge::topics::VOLUME_CHANGE, volume, &mut None),
+            [
+                ("yt-hear/volume".to_string(), true, "35".to_string()),
+                ("yt-hear/volume_level".to_string(), true, "0.35".to_string()),
+                ("yt-hear/muted".to_string(), true, "OFF".to_string()),
+            ]
+        );
+        let state = bridge::topics::sample(bridge::topics::STATE_CHANGE);
+        assert_eq!(
+            messages(bridge::topics::STATE_CHANGE, state, &mut None),
+            [
+                ("yt-hear/shuffle".to_string(), true, "ON".to_string()),
+                ("yt-hear/repeat".to_string(), true, "ALL".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn publish_errors_are_printed_once_per_interval() {
+        let mut log = ErrorLog::default();
+        let start = Instant::now();
+        assert_eq!(log.should_log(start), Some(0));
+        assert_eq!(log.should_log(start + Duration::from_secs(1)), None);
+        assert_eq!(log.should_log(start + Duration::from_secs(2)), None);
+        assert_eq!(log.should_log(start + PUBLISH_ERROR_LOG_INTERVAL), Some(2));
+    }
+
+    #[test]
+    fn volume_and_seek_are_range_checked() {
+        assert_eq!(
+            command("yt-hear/volume_level/set", "0.5"),
+            Some(json!({ "action": "setVolume", "value": 50.0 }))
+        );
+        assert_eq!(command("yt-hear/volume/set", "101"), None);
+        assert_eq!(command("yt-hear/volume/set", ""), None);
+        assert_eq!(command("yt-hear/seek/set", "-1"), None);
+        assert_eq!(command("yt-hear/unknown", "1"), None);
+    }
+}
@@ -0,0 +1,353 @@
+// src-tauri/src/now_playing.rs
+
+//! Now-playing text files for streaming tools that can only read files.
+//!
+//! On every `song-info` change the writer updates `now_playing.txt` (from a
+//! format template), `title.txt`, `artist.txt`, `album.txt` and the cover
+//! (`cover.jpg`, `.png`, `.webp` or `.gif`, whatever the image really is).
+//! Each file is written to a temporary name and renamed over the old one, so
+//! readers never see a partial file.
+
+use crate::bridge::topics::{self, SongInfo};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+use tauri::{AppHandle, Listener, Manager};
+
+const DEFAULT_DIR: &str = "now-playing";
+const COVER_TIMEOUT_SECS: u64 = 10;
+const MAX_COVER_BYTES: u64 = 10 * 1024 * 1024;
+/// Cover file extensions and their MIME types; only one `cover.*` exists at a time.
+const COVER_FORMATS: &[(&str, &str)] = &[
+    ("jpg", "image/jpeg"),
+    ("png", "image/png"),
+    ("webp", "image/webp"),
+    ("gif", "image/gif"),
+];
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NowPlayingSettings {
+    pub enabled: bool,
+    /// Output folder; `<app_data_dir>/now-playing` when unset. The cover is
+    /// `cover.jpg`, `.png`, `.webp` or `.gif`, after the image's real format.
+    pub directory: Option<PathBuf>,
+    /// Text of `now_playing.txt`. Placeholders: `{title}`, `{artist}`,
+    /// `{album}`, `{duration}` and `{url}`.
+    pub template: String,
+}
+
+impl Default for NowPlayingSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            directory: None,
+            template: "{artist} - {title}".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Track {
+    title: String,
+    artist: String,
+    album: String,
+    duration: u64,
+    url: String,
+    image: String,
+}
+
+impl Track {
+    fn from_song(song: &Value) -> Self {
+        let song: SongInfo = topics::parse(song).unwrap_or_default();
+        Self {
+            title: song.title,
+            artist: song.artist,
+            album: song.album,
+            duration: song.duration,
+            url: song.url,
+            image: song.image_src,
+        }
+    }
+
+    fn render(&self, template: &str) -> String {
+        template
+            .replace("{title}", &self.title)
+            .replace("{artist}", &self.artist)
+            .replace("{album}", &self.album)
+            .replace(
+                "{duration}",
+                &format!("{}:{:02}", self.duration / 60, self.duration % 60),
+            )
+            .replace("{url}", &self.url)
+    }
+}
+
+/// Starts the writer when it is enabled in the preferences.
+pub fn start(app: &AppHandle) {
+    let settings = app
+        .state::<crate::preferences::PreferencesStore>()
+        .get()
+        .now_playing;
+    if !settings.enabled {
+        return;
+    }
+    let dir = match &settings.directory {
+        Some(dir) => dir.clone(),
+        None => match app.path().app_data_dir() {
+            Ok(data) => data.join(DEFAULT_DIR),
+            Err(e) => {
+                eprintln!("❌ Now playing: no output directory: {}", e);
+                return;
+            }
+        },
+    };
+    if let Err(e) = fs::create_dir_all(&dir) {
+        eprintln!("❌ Now playing: cannot create {}: {}", dir.display(), e);
+        return;
+    }
+    println!("📝 Now playing files in {}", dir.display());
+
+    // song-info also fires on play/pause; a single worker skips unchanged tracks
+    let (tx, rx) = mpsc::channel::<Track>();
+    app.listen_any(topics::event(topics::SONG_INFO), move |event| {
+        let song = serde_json::from_str::<Value>(event.payload()).unwrap_or(Value::Null);
+        let _ = tx.send(Track::from_song(&song));
+    });
+    std::thread::spawn(move || {
+        let mut current: Option<Track> = None;
+        for track in rx {
+            if let Err(e) = update(&dir, &settings.template, track, &mut current) {
+                eprintln!("❌ Now playing write error: {}", e);
+            }
+        }
+    });
+}
+
+/// Writes `track` unless it is what the files already show. `current` only
+/// moves to a track once it is fully written; after a failure the files may
+/// be half updated, so the next event writes everything again.
+fn update(
+    dir: &Path,
+    template: &str,
+    track: Track,
+    current: &mut Option<Track>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    if current.as_ref() == Some(&track) {
+        return Ok(());
+    }
+    let written = write_track(dir, template, &track, current.as_ref());
+    *current = written.is_ok().then_some(track);
+    written
+}
+
+fn write_track(
+    dir: &Path,
+    template: &str,
+    track: &Track,
+    previous: Option<&Track>,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let playing = !track.title.is_empty();
+    let now_playing = if playing {
+        track.render(template)
+    } else {
+        String::new()
+    };
+    write_atomic(&dir.join("now_playing.txt"), now_playing.as_bytes())?;
+    write_atomic(&dir.join("title.txt"), track.title.as_bytes())?;
+    write_atomic(&dir.join("artist.txt"), track.artist.as_bytes())?;
+    write_atomic(&dir.join("album.txt"), track.album.as_bytes())?;
+
+    if track.image.is_empty() {
+        write_cover(dir, None)?;
+    } else if previous.map(|p| p.image.as_str()) != Some(track.image.as_str()) {
+        match fetch_cover(&track.image) {
+            Ok((bytes, extension)) => write_cover(dir, Some((&bytes, extension)))?,
+            Err(e) => {
+                // The cover on disk belongs to the previous track
+                write_cover(dir, None)?;
+                return Err(e);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Downloads the cover and returns it with its file extension.
+fn fetch_cover(url: &str) -> Result<(Vec<u8>, &'static str), Box<dyn std::error::Error>> {
+    let (bytes, content_type) = download(url)?;
+    let extension = cover_extension(&bytes, content_type.as_deref())
+        .ok_or("Cover is not a JPEG, PNG, WebP or GIF image")?;
+    Ok((bytes, extension))
+}
+
+/// Returns the body and its `Content-Type`.
+fn download(url: &str) -> Result<(Vec<u8>, Option<String>), Box<dyn std::error::Error>> {
+    let response = ureq::get(url)
+        .timeout(Duration::from_secs(COVER_TIMEOUT_SECS))
+        .call()?;
+    let content_type = response.header("Content-Type").map(str::to_string);
+    let mut bytes = Vec::new();
+    response
+        .into_reader()
+        .take(MAX_COVER_BYTES)
+        .read_to_end(&mut bytes)?;
+    Ok((bytes, content_type))
+}
+
+/// Picks the extension from the image's magic bytes; the `Content-Type` is
+/// only a fallback because CDNs often mislabel thumbnails.
+fn cover_extension(bytes: &[u8], content_type: Option<&str>) -> Option<&'static str> {
+    let sniffed = if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some("jpg")
+    } else if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some("png")
+    } else if bytes.len() >= 12 && &bytes[..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some("webp")
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some("gif")
+    } else {
+        None
+    };
+    sniffed.or_else(|| {
+        let mime = content_type?.split(';').next()?.trim().to_ascii_lowercase();
+        COVER_FORMATS
+            .iter()
+            .find(|(_, format_mime)| *format_mime == mime)
+            .map(|(extension, _)| *extension)
+    })
+}
+
+/// Writes `cover.<extension>` and removes covers left with other extensions;
+/// `None` removes every cover.
+fn write_cover(dir: &Path, cover: Option<(&[u8], &str)>) -> std::io::Result<()> {
+    if let Some((bytes, extension)) = cover {
+        write_atomic(&dir.join(format!("cover.{}", extension)), bytes)?;
+    }
+    for (extension, _) in COVER_FORMATS {
+        if cover.map(|(_, current)| current) == Some(*extension) {
+            continue;
+        }
+        match fs::remove_file(dir.join(format!("cover.{}", extension))) {
+            Err(e) if e.kind() != std::io::ErrorKind::NotFound => return Err(e),
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+/// Writes next to `path` and renames over it; a rename within one folder is atomic.
+fn write_atomic(path: &Path, data: &[u8]) -> std::io::Result<()> {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    let tmp = path.with_file_name(format!(".{}.tmp", name));
+    fs::write(&tmp, data)?;
+    fs::rename(&tmp, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn extension_from_magic_bytes() {
+        assert_eq!(
+            cover_extension(&[0xFF, 0xD8, 0xFF, 0xE0], None),
+            Some("jpg")
+        );
+        assert_eq!(
+            cover_extension(b"\x89PNG\r\n\x1a\n\0\0\0\rIHDR", None),
+            Some("png")
+        );
+        assert_eq!(
+            cover_extension(b"RIFF\x24\0\0\0WEBPVP8 ", None),
+            Some("webp")
+        );
+        assert_eq!(cover_extension(b"GIF89a\x01\0", None), Some("gif"));
+    }
+
+    #[test]
+    fn magic_bytes_win_over_content_type() {
+        assert_eq!(
+            cover_extension(b"RIFF\x24\0\0\0WEBPVP8 ", Some("image/jpeg")),
+            Some("webp")
+        );
+    }
+
+    #[test]
+    fn content_type_is_the_fallback() {
+        assert_eq!(
+            cover_extension(b"????", Some("image/PNG; charset=binary")),
+            Some("png")
+        );
+        assert_eq!(cover_extension(b"<html>", Some("text/html")), None);
+        assert_eq!(cover_extension(b"", None), None);
+    }
+
+    #[test]
+    fn a_failed_write_is_retried_on_the_next_event() {
+        let dir =
+            std::env::temp_dir().join(format!("yt-hear-now-playing-test-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        let track = Track {
+            title: "Song".to_string(),
+            artist: "Artist".to_string(),
+            ..Track::default()
+        };
+        let mut current = None;
+
+        assert!(update(&dir, "{artist} - {title}", track.clone(), &mut current).is_err());
+        assert_eq!(current, None);
+
+        fs::create_dir_all(&dir).unwrap();
+        update(&dir, "{artist} - {title}", track.clone(), &mut current).unwrap();
+        assert_eq!(current, Some(track));
+        assert_eq!(
+            fs::read_to_string(dir.join("now_playing.txt")).unwrap(),
+            "Artist - Song"
+        );
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn a_failed_cover_download_removes_the_old_cover() {
+        let dir =
+            std::env::temp_dir().join(format!("yt-hear-stale-cover-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        write_cover(&dir, Some((&b"jpeg"[..], "jpg"))).unwrap();
+        let track = Track {
+            title: "Song".to_string(),
+            // Nothing listens on the discard port
+            image: "http://127.0.0.1:9/cover".to_string(),
+            ..Track::default()
+        };
+
+        let mut current = None;
+        assert!(update(&dir, "{title}", track, &mut current).is_err());
+        assert_eq!(current, None);
+        assert!(!dir.join("cover.jpg").exists());
+        assert_eq!(fs::read_to_string(dir.join("title.txt")).unwrap(), "Song");
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn only_the_current_cover_is_kept() {
+        let dir = std::env::temp_dir().join(format!("yt-hear-cover-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+
+        write_cover(&dir, Some((&b"jpeg"[..], "jpg"))).unwrap();
+        write_cover(&dir, Some((&b"webp"[..], "webp"))).unwrap();
+        assert!(!dir.join("cover.jpg").exists());
+        assert_eq!(fs::read(dir.join("cover.webp")).unwrap(), b"webp");
+
+        write_cover(&dir, None).unwrap();
+        assert!(!dir.join("cover.webp").exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
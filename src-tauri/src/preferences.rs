@@ -0,0 +1,273 @@
+// src-tauri/src/preferences.rs
+
+//! App-level preferences persisted in `<app_data_dir>/preferences.json`.
+
+use crate::http_server::{ApiListen, RateLimits};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const PREFERENCES_FILE: &str = "preferences.json";
+
+/// What the window close button does.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CloseBehavior {
+    #[default]
+    HideToTray,
+    Minimize,
+    Quit,
+}
+
+impl CloseBehavior {
+    pub const ALL: [CloseBehavior; 3] = [Self::HideToTray, Self::Minimize, Self::Quit];
+
+    pub fn key(self) -> &'static str {
+        match self {
+            Self::HideToTray => "hide_to_tray",
+            Self::Minimize => "minimize",
+            Self::Quit => "quit",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::HideToTray => "Hide to Tray",
+            Self::Minimize => "Minimize",
+            Self::Quit => "Quit",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<Self> {
+        Self::ALL.into_iter().find(|b| b.key() == key)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Preferences {
+    /// Create the main window hidden and leave only the tray icon.
+    pub start_minimized: bool,
+    /// Start the app when the user logs in.
+    pub launch_at_login: bool,
+    pub close_behavior: CloseBehavior,
+    /// Set once the "still running in the tray" notice has been shown.
+    pub close_notice_shown: bool,
+    /// Listeners used the next time the API server starts.
+    pub api_listen: ApiListen,
+    /// Per-client limits for the API server, applied on its next start.
+    pub api_rate_limits: RateLimits,
+    /// MQTT / Home Assistant publisher, read at startup.
+    pub mqtt: crate::mqtt::MqttSettings,
+    /// Text files for streaming software, read at startup.
+    pub now_playing: crate::now_playing::NowPlayingSettings,
+    /// Lyrics lookups, read at startup.
+    pub lyrics: crate::lyrics::LyricsSettings,
+}
+
+#[derive(Default)]
+pub struct PreferencesStore {
+    current: Mutex<Preferences>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+impl PreferencesStore {
+    pub fn load(&self, app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = app.path().app_data_dir()?;
+        fs::create_dir_all(&dir)?;
+        self.load_from(dir.join(PREFERENCES_FILE))
+    }
+
+    fn load_from(&self, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        *self.path.lock().unwrap() = Some(path.clone());
+
+        if let Ok(raw) = fs::read_to_string(&path) {
+            match serde_json::from_str(&raw) {
+                Ok(preferences) => *self.current.lock().unwrap() = preferences,
+                Err(e) => {
+                    // Keep the user's file; the next update writes defaults next to it
+                    let backup = path.with_extension("json.bak");
+                    fs::rename(&path, &backup)?;
+                    return Err(format!("{} (moved to {})", e, backup.display()).into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn get(&self) -> Preferences {
+        self.current.lock().unwrap().clone()
+    }
+
+    /// Applies `change` and writes the result to disk.
+    pub fn update(&self, change: impl FnOnce(&mut Preferences)) -> Result<Preferences, String> {
+        let updated = {
+            let mut current = self.current.lock().unwrap();
+            change(&mut current);
+            current.clone()
+        };
+        self.save(&updated)?;
+        Ok(updated)
+    }
+
+    fn save(&self, preferences: &Preferences) -> Result<(), String> {
+        let Some(path) = self.path.lock().unwrap().clone() else {
+            return Ok(());
+        };
+        let data = serde_json::to_string_pretty(preferences).map_err(|e| e.to_string())?;
+        fs::write(&path, data).map_err(|e| e.to_string())
+    }
+}
+
+/// Launch at login through an XDG autostart entry (`~/.config/autostart/<name>.desktop`).
+pub mod autostart {
+    #[cfg(target_os = "linux")]
+    use std::{fs, path::PathBuf};
+    use tauri::AppHandle;
+    #[cfg(target_os = "linux")]
+    use tauri::Manager;
+
+    #[cfg(target_os = "linux")]
+    fn entry_path(app: &AppHandle) -> Result<PathBuf, String> {
+        let dir = app
+            .path()
+            .config_dir()
+            .map_err(|e| e.to_string())?
+            .join("autostart");
+        Ok(dir.join(format!("{}.desktop", app.package_info().name)))
+    }
+
+    /// Path of the running binary; AppImages must point at the image, not the mount.
+    #[cfg(target_os = "linux")]
+    fn exec_path() -> Result<PathBuf, String> {
+        match std::env::var_os("APPIMAGE") {
+            Some(appimage) => Ok(PathBuf::from(appimage)),
+            None => std::env::current_exe().map_err(|e| e.to_string()),
+        }
+    }
+
+    /// Quotes `path` as a single `Exec` argument: the quoting rules of the
+    /// desktop entry spec first, then the escapes of any string value.
+    #[cfg(any(target_os = "linux", test))]
+    pub(super) fn exec_arg(path: &str) -> String {
+        let mut quoted = String::from("\"");
+        for c in path.chars() {
+            match c {
+                '"' | '`' | '$' | '\\' => {
+                    quoted.push('\\');
+                    quoted.push(c);
+                }
+                '%' => quoted.push_str("%%"),
+                _ => quoted.push(c),
+            }
+        }
+        quoted.push('"');
+
+        quoted
+            .replace('\\', "\\\\")
+            .replace('\n', "\\n")
+            .replace('\t', "\\t")
+            .replace('\r', "\\r")
+    }
+
+    #[cfg(target_os = "linux")]
+    pub fn set_enabled(app: &AppHandle, enabled: bool) -> Result<(), String> {
+        let path = entry_path(app)?;
+        if !enabled {
+            return match fs::remove_file(&path) {
+                Err(e) if e.kind() != std::io::ErrorKind::NotFound => Err(e.to_string()),
+                _ => Ok(()),
+            };
+        }
+
+        let name = &app.package_info().name;
+        let entry = format!(
+            "[Desktop Entry]\n\
+             Type=Application\n\
+             Name={name}\n\
+             Comment=YouTube Music desktop client\n\
+             Exec={exec}\n\
+             Terminal=false\n\
+             X-GNOME-Autostart-enabled=true\n",
+            exec = exec_arg(&exec_path()?.to_string_lossy()),
+        );
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(|e| e.to_string())?;
+        }
+        fs::write(&path, entry).map_err(|e| e.to_string())
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    pub fn set_enabled(_app: &AppHandle, _enabled: bool) -> Result<(), String> {
+        Err("Launch at login is only supported on Linux".to_string())
+    }
+}
+
+/// Keeps the autostart entry in line with the stored flag, e.g. after the binary moved.
+pub fn sync_autostart(app: &AppHandle) {
+    let enabled = app.state::<PreferencesStore>().get().launch_at_login;
+    if let Err(e) = autostart::set_enabled(app, enabled) {
+        if enabled {
+            eprintln!("⚠️ Autostart error: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn exec_arg_quotes_plain_paths() {
+        assert_eq!(
+            autostart::exec_arg("/opt/yt hear/yt-hear"),
+            "\"/opt/yt hear/yt-hear\""
+        );
+    }
+
+    #[test]
+    fn exec_arg_escapes_reserved_characters() {
+        // `"` `$` and `` ` `` get one backslash, which the string escape doubles
+        assert_eq!(autostart::exec_arg("/a\"b"), r#""/a\\"b""#);
+        assert_eq!(autostart::exec_arg("/$HOME/`x`"), r#""/\\$HOME/\\`x\\`""#);
+        // A literal backslash ends up as four
+        assert_eq!(autostart::exec_arg("/a\\b"), r#""/a\\\\b""#);
+        assert_eq!(autostart::exec_arg("/100%/app"), "\"/100%%/app\"");
+        assert_eq!(autostart::exec_arg("/a\nb"), r#""/a\nb""#);
+    }
+
+    #[test]
+    fn unreadable_preferences_are_backed_up_not_overwritten() {
+        let dir = std::env::temp_dir().join(format!("yt-hear-prefs-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(PREFERENCES_FILE);
+        fs::write(&path, r#"{"start_minimized": tru"#).unwrap();
+
+        let store = PreferencesStore::default();
+        assert!(store.load_from(path.clone()).is_err());
+        store.update(|p| p.start_minimized = true).unwrap();
+
+        let backup = fs::read_to_string(path.with_extension("json.bak")).unwrap();
+        assert_eq!(backup, r#"{"start_minimized": tru"#);
+        let saved: Preferences = serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert!(saved.start_minimized);
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn missing_fields_fall_back_to_defaults() {
+        let dir = std::env::temp_dir().join(format!("yt-hear-prefs-old-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(PREFERENCES_FILE);
+        fs::write(&path, r#"{"close_behavior": "quit"}"#).unwrap();
+
+        let store = PreferencesStore::default();
+        store.load_from(path).unwrap();
+        let loaded = store.get();
+        assert_eq!(loaded.close_behavior, CloseBehavior::Quit);
+        assert!(!loaded.start_minimized);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
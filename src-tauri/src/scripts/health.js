@@ -0,0 +1,26 @@
+// scripts/health.js
+// Lo inyecta Rust antes que cualquier otro script en cada carga de página.
+(function () {
+    window.Pear = window.Pear || {};
+    if (window.Pear.Health) return;
+
+    const send = (script, stage, ok, detail) => {
+        try {
+            window.__TAURI__?.core?.invoke('report_script_health', {
+                loadId: window.Pear.Health.loadId,
+                script,
+                stage,
+                ok: !!ok,
+                detail: detail || null,
+            }).catch(() => { });
+        } catch (e) { }
+    };
+
+    window.Pear.Health = {
+        loadId: 0,
+        // El script terminó de evaluarse sin lanzar excepciones (lo añade Rust)
+        loaded: (script) => send(script, 'loaded', true),
+        // El script encontró lo que necesita en el DOM (lo llama cada provider)
+        report: (script, ok, detail) => send(script, 'ready', ok, detail),
+    };
+})();
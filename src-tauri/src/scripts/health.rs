@@ -0,0 +1,139 @@
+// src-tauri/src/scripts/health.rs
+
+//! Per page load record of which scripts were injected, ran and initialised.
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::Mutex;
+
+/// Defines `window.Pear.Health`; injected before every other script.
+pub const PRELUDE: &str = include_str!("./health.js");
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ScriptEntry {
+    /// `eval` was dispatched to the webview.
+    pub injected: bool,
+    pub error: Option<String>,
+    /// The script body ran to the end without throwing.
+    pub loaded: bool,
+    /// Reported by providers once they find their DOM (`None` = not reported yet).
+    pub ready: Option<bool>,
+    pub detail: Option<String>,
+    pub updated_at: Option<DateTime<Utc>>,
+}
+
+impl ScriptEntry {
+    fn is_healthy(&self) -> bool {
+        self.injected && self.error.is_none() && self.loaded && self.ready != Some(false)
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PageHealth {
+    pub load_id: u64,
+    pub url: String,
+    pub started_at: Option<DateTime<Utc>>,
+    pub scripts: BTreeMap<String, ScriptEntry>,
+}
+
+#[derive(Default)]
+pub struct ScriptHealth {
+    current: Mutex<PageHealth>,
+}
+
+impl ScriptHealth {
+    /// Starts a fresh health map and returns its id; reports for older ids are ignored.
+    pub fn begin_load(&self, url: &str) -> u64 {
+        let mut current = self.current.lock().unwrap();
+        let load_id = current.load_id + 1;
+        *current = PageHealth {
+            load_id,
+            url: url.to_string(),
+            started_at: Some(Utc::now()),
+            scripts: BTreeMap::new(),
+        };
+        load_id
+    }
+
+    /// Id of the page load being recorded.
+    pub fn current_load_id(&self) -> u64 {
+        self.current.lock().unwrap().load_id
+    }
+
+    pub fn record_injection(&self, load_id: u64, script: &str, result: Result<(), String>) {
+        self.update(load_id, script, |entry| {
+            entry.injected = result.is_ok();
+            entry.error = result.err();
+        });
+    }
+
+    pub fn record_report(
+        &self,
+        load_id: u64,
+        script: &str,
+        stage: &str,
+        ok: bool,
+        detail: Option<String>,
+    ) {
+        self.update(load_id, script, |entry| {
+            match stage {
+                "loaded" => entry.loaded = ok,
+                _ => entry.ready = Some(ok),
+            }
+            if detail.is_some() {
+                entry.detail = detail;
+            }
+        });
+    }
+
+    fn update(&self, load_id: u64, script: &str, f: impl FnOnce(&mut ScriptEntry)) {
+        let mut current = self.current.lock().unwrap();
+        if current.load_id != load_id {
+            return;
+        }
+        let entry = current.scripts.entry(script.to_string()).or_default();
+        f(entry);
+        entry.updated_at = Some(Utc::now());
+    }
+
+    pub fn snapshot(&self) -> serde_json::Value {
+        let current = self.current.lock().unwrap();
+        let unhealthy: Vec<&String> = current
+            .scripts
+            .iter()
+            .filter(|(_, entry)| !entry.is_healthy())
+            .map(|(name, _)| name)
+            .collect();
+
+        serde_json::json!({
+            "load_id": current.load_id,
+            "url": current.url,
+            "started_at": current.started_at,
+            "healthy": unhealthy.is_empty(),
+            "unhealthy": unhealthy,
+            "scripts": current.scripts,
+        })
+    }
+}
+
+/// Appended to each built-in script so `loaded` only fires if the body did not throw.
+pub fn with_loaded_marker(script_key: &str, source: &str) -> String {
+    format!(
+        "{}\n;window.Pear?.Health?.loaded({});",
+        source,
+        serde_json::to_string(script_key).unwrap_or_default()
+    )
+}
+
+#[tauri::command]
+pub fn report_script_health(
+    health: tauri::State<'_, ScriptHealth>,
+    load_id: u64,
+    script: String,
+    stage: String,
+    ok: bool,
+    detail: Option<String>,
+) {
+    health.record_report(load_id, &script, &stage, ok, detail);
+}
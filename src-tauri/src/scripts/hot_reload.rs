@@ -0,0 +1,96 @@
+// src-tauri/src/scripts/hot_reload.rs
+
+//! Debug-only: serves scripts from disk and re-injects them when they change,
+//! so editing `providers/*.js` does not need a Rust rebuild.
+//! The directory is `$YT_HEAR_SCRIPTS_DIR`, or this crate's `src/scripts`.
+
+use super::health::ScriptHealth;
+use super::injector;
+use super::settings::ScriptSettings;
+use super::ScriptId;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::{AppHandle, Manager};
+
+const SCRIPTS_DIR_ENV: &str = "YT_HEAR_SCRIPTS_DIR";
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+pub fn scripts_dir() -> Option<PathBuf> {
+    std::env::var_os(SCRIPTS_DIR_ENV)
+        .map(PathBuf::from)
+        .unwrap_or_else(|| PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src/scripts")))
+        .canonicalize()
+        .ok()
+        .filter(|dir| dir.is_dir())
+}
+
+pub fn read_from_disk(id: ScriptId) -> Option<String> {
+    fs::read_to_string(scripts_dir()?.join(id.relative_path())).ok()
+}
+
+fn modified_at(dir: &Path, id: ScriptId) -> Option<SystemTime> {
+    fs::metadata(dir.join(id.relative_path()))
+        .and_then(|m| m.modified())
+        .ok()
+}
+
+/// Polls the scripts directory and re-injects changed scripts into the main webview.
+pub fn watch(app: AppHandle) {
+    let Some(dir) = scripts_dir() else {
+        return;
+    };
+    println!("👀 Hot reload: watching {}", dir.display());
+
+    std::thread::spawn(move || {
+        let mut known: HashMap<ScriptId, Option<SystemTime>> = ScriptId::ALL_IN_ORDER
+            .into_iter()
+            .map(|id| (id, modified_at(&dir, id)))
+            .collect();
+
+        loop {
+            std::thread::sleep(POLL_INTERVAL);
+            for id in ScriptId::ALL_IN_ORDER {
+                let modified = modified_at(&dir, id);
+                if known.get(&id) == Some(&modified) {
+                    continue;
+                }
+                known.insert(id, modified);
+                reinject(&app, id);
+            }
+        }
+    });
+}
+
+fn reinject(app: &AppHandle, id: ScriptId) {
+    if !app.state::<ScriptSettings>().is_enabled(id) {
+        println!(
+            "🔥 Hot reload: {} changed but is disabled",
+            id.relative_path()
+        );
+        return;
+    }
+    let Some(window) = app.get_webview_window(crate::MAIN_WINDOW_LABEL) else {
+        return;
+    };
+    let Some(source) = read_from_disk(id) else {
+        eprintln!("❌ Hot reload: cannot read {}", id.relative_path());
+        return;
+    };
+
+    // Same wrapper as on page load, with the guard key reset so it runs again
+    let result = window
+        .eval(&injector::reinjectable(id.key(), &source))
+        .map_err(|e| e.to_string());
+    match &result {
+        Ok(()) => println!(
+            "🔥 Hot reload: re-injected [{:?}] {}",
+            id,
+            id.relative_path()
+        ),
+        Err(e) => eprintln!("❌ Hot reload [{:?}]: {}", id, e),
+    }
+    let health = app.state::<ScriptHealth>();
+    health.record_injection(health.current_load_id(), id.key(), result);
+}
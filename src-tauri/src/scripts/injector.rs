@@ -0,0 +1,225 @@
+// src-tauri/src/scripts/injector.rs
+
+//! Injects the built-in scripts and plugins exactly once per document.
+//!
+//! `on_page_load` fires for both `Started` and `Finished`, and reloads or
+//! redirects can repeat them. Scripts are injected on `Finished`: at `Started`
+//! the new DOM does not exist yet and the eval can land in the outgoing
+//! document. Two layers keep scripts from running twice: `LoadTracker` skips
+//! a repeated `Finished` for a document already injected, and every script is
+//! wrapped in a guard keyed on `window.__YTH_INJECTED__`, which lives on the
+//! document's own `window`.
+//!
+//! The guard runs each script inside a function, so its top-level
+//! declarations are private; anything meant for the console or other scripts
+//! has to be assigned to `window` explicitly.
+
+use super::health::{self, ScriptHealth};
+use super::plugins::PluginRegistry;
+use super::settings::ScriptSettings;
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::webview::{PageLoadEvent, PageLoadPayload};
+use tauri::{Manager, Runtime, Webview};
+
+/// The last document injected into a webview.
+struct Document {
+    url: String,
+    finished: bool,
+}
+
+/// Tracks, per webview, which document was last injected.
+#[derive(Default)]
+pub struct LoadTracker {
+    injected: Mutex<HashMap<String, Document>>,
+}
+
+impl LoadTracker {
+    /// Whether this page-load event should trigger an injection.
+    pub fn should_inject(&self, webview: &str, event: PageLoadEvent, url: &str) -> bool {
+        let mut injected = self.injected.lock().unwrap();
+        match event {
+            // A new document is on its way; it is injected once it finishes
+            PageLoadEvent::Started => {
+                injected.insert(
+                    webview.to_string(),
+                    Document {
+                        url: url.to_string(),
+                        finished: false,
+                    },
+                );
+                false
+            }
+            PageLoadEvent::Finished => match injected.get_mut(webview) {
+                // The document from Started, even if the page rewrote its URL meanwhile
+                Some(doc) if !doc.finished => {
+                    doc.url = url.to_string();
+                    doc.finished = true;
+                    true
+                }
+                Some(doc) if doc.url == url => false,
+                // Started never ran for this document
+                _ => {
+                    injected.insert(
+                        webview.to_string(),
+                        Document {
+                            url: url.to_string(),
+                            finished: true,
+                        },
+                    );
+                    true
+                }
+            },
+        }
+    }
+}
+
+/// Wraps a script so it runs at most once per document and reports `loaded`
+/// to `window.Pear.Health` only if its body did not throw. The body runs in
+/// its own function scope.
+pub fn idempotent(key: &str, source: &str) -> String {
+    let key_json = serde_json::to_string(key).unwrap_or_default();
+    format!(
+        "if (!(window.__YTH_INJECTED__ = window.__YTH_INJECTED__ || new Set()).has({key})) {{\n\
+         window.__YTH_INJECTED__.add({key});\n\
+         (function () {{\n\
+         {body}\n\
+         }})();\n\
+         }}",
+        key = key_json,
+        body = health::with_loaded_marker(key, source),
+    )
+}
+
+/// Like `idempotent`, but first clears the guard key so the script runs again
+/// in the current document (hot reload).
+pub fn reinjectable(key: &str, source: &str) -> String {
+    format!(
+        "window.__YTH_INJECTED__?.delete({});\n{}",
+        serde_json::to_string(key).unwrap_or_default(),
+        idempotent(key, source)
+    )
+}
+
+pub fn on_page_load<R: Runtime>(webview: &Webview<R>, payload: &PageLoadPayload<'_>) {
+    let url = payload.url().as_str();
+    let tracker = webview.state::<LoadTracker>();
+    if !tracker.should_inject(webview.label(), payload.event(), url) {
+        return;
+    }
+
+    println!("💉 Injecting Scripts ({:?}) into {}", payload.event(), url);
+    let script_health = webview.state::<ScriptHealth>();
+    let load_id = script_health.begin_load(url);
+    // The prelude is also an initialization script; evaluating it again is a no-op
+    if let Err(e) = webview.eval(health::PRELUDE) {
+        eprintln!("❌ Health prelude error: {}", e);
+    }
+    let _ = webview.eval(&format!("window.Pear.Health.loadId = {};", load_id));
+
+    for script_id in webview.state::<ScriptSettings>().injection_order() {
+        let script = idempotent(script_id.key(), &script_id.content());
+        let result = webview.eval(&script).map_err(|e| e.to_string());
+        if let Err(e) = &result {
+            eprintln!("❌ Script Error [{:?}]: {}", script_id, e);
+        }
+        script_health.record_injection(load_id, script_id.key(), result);
+    }
+
+    let registry = webview.state::<PluginRegistry>();
+    for (plugin_id, file, script) in registry.injectable_scripts() {
+        let key = super::plugins::script_key(&plugin_id, &file);
+        let script = idempotent(&key, &script);
+        let result = webview.eval(&script).map_err(|e| e.to_string());
+        if let Err(e) = &result {
+            registry.mark_failed(&plugin_id, e.clone());
+        }
+        script_health.record_injection(load_id, &key, result);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use PageLoadEvent::{Finished, Started};
+
+    /// Counts how many times a sequence of events triggers an injection.
+    fn injections(tracker: &LoadTracker, events: &[(PageLoadEvent, &str)]) -> usize {
+        events
+            .iter()
+            .filter(|(event, url)| tracker.should_inject("main", *event, url))
+            .count()
+    }
+
+    const HOME: &str = "https://music.youtube.com/";
+    const LIBRARY: &str = "https://music.youtube.com/library";
+
+    #[test]
+    fn started_then_finished_injects_once_on_finished() {
+        let tracker = LoadTracker::default();
+        assert!(!tracker.should_inject("main", Started, HOME));
+        assert!(tracker.should_inject("main", Finished, HOME));
+    }
+
+    #[test]
+    fn finished_without_started_injects_once() {
+        let tracker = LoadTracker::default();
+        assert_eq!(
+            injections(&tracker, &[(Finished, HOME), (Finished, HOME)]),
+            1
+        );
+    }
+
+    #[test]
+    fn same_url_reload_injects_again() {
+        let tracker = LoadTracker::default();
+        let reload = [(Started, HOME), (Finished, HOME)];
+        assert_eq!(injections(&tracker, &reload), 1);
+        assert_eq!(injections(&tracker, &reload), 1);
+        // A load that never finishes injects nothing
+        assert_eq!(injections(&tracker, &[(Started, HOME)]), 0);
+        assert_eq!(injections(&tracker, &[(Finished, HOME)]), 1);
+    }
+
+    #[test]
+    fn url_rewritten_during_load_is_the_same_document() {
+        let tracker = LoadTracker::default();
+        assert_eq!(
+            injections(&tracker, &[(Started, HOME), (Finished, LIBRARY)]),
+            1
+        );
+        // A later Finished for the rewritten URL is still that document
+        assert_eq!(injections(&tracker, &[(Finished, LIBRARY)]), 0);
+        assert_eq!(
+            injections(&tracker, &[(Started, HOME), (Finished, HOME)]),
+            1
+        );
+    }
+
+    #[test]
+    fn webviews_are_tracked_separately() {
+        let tracker = LoadTracker::default();
+        assert!(!tracker.should_inject("main", Started, HOME));
+        assert!(!tracker.should_inject("mini", Started, HOME));
+        assert!(tracker.should_inject("main", Finished, HOME));
+        assert!(tracker.should_inject("mini", Finished, HOME));
+        assert!(!tracker.should_inject("main", Finished, HOME));
+    }
+
+    #[test]
+    fn guard_scopes_the_body_to_a_function() {
+        let script = idempotent("providers/debug.js", "const x = 1;");
+        assert!(script.contains("(function () {\nconst x = 1;"));
+        assert!(script.contains(".has(\"providers/debug.js\")"));
+    }
+
+    #[test]
+    fn reinjectable_clears_the_guard_before_the_wrapped_script() {
+        let script = reinjectable("yt_debug", "const x = 1;");
+        let guard = idempotent("yt_debug", "const x = 1;");
+        assert_eq!(
+            script,
+            format!("window.__YTH_INJECTED__?.delete(\"yt_debug\");\n{}", guard)
+        );
+    }
+}
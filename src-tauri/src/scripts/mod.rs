@@ -1,60 +1,258 @@
-// src-tauri/src/scripts/mod.rs
-
-/// Identificador único para cada script que inyectamos
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub enum ScriptId {
-    // Core
-    Logger,
-    Adblock,
-    TauriBridge,
-
-    // Providers
-    YtMusicInfo,
-    YtPlayerState,
-    YtQueueController,
-
-    YtMusicObserver,
-    YtPlayerListener,
-
-    YtMusicController,
-    YtMusicSearch,
-
-    // Debug
-    YtDebug,
-
-    ServerControl,
-}
-
-impl ScriptId {
-    pub const fn content(self) -> &'static str {
-        match self {
-            ScriptId::Logger => include_str!("./logger.js"),
-            ScriptId::Adblock => include_str!("./adblock.js"),
-            ScriptId::YtMusicInfo => include_str!("./providers/songinfo.js"),
-            ScriptId::TauriBridge => include_str!("./bridge.js"),
-            ScriptId::YtPlayerState => include_str!("./providers/playerstate.js"),
-            ScriptId::YtQueueController => include_str!("./providers/queuecontroller.js"),
-            ScriptId::YtMusicObserver => include_str!("./providers/observer.js"),
-            ScriptId::YtPlayerListener => include_str!("./providers/playerListeners.js"),
-            ScriptId::YtMusicController => include_str!("./providers/controller.js"),
-            ScriptId::YtDebug => include_str!("./providers/debug.js"),
-            ScriptId::ServerControl => include_str!("./server_control.js"),
-            ScriptId::YtMusicSearch => include_str!("./providers/search.js"),
-        }
-    }
-
-    pub const ALL_IN_ORDER: [ScriptId; 12] = [
-        ScriptId::Logger,
-        ScriptId::Adblock,
-        ScriptId::TauriBridge,
-        ScriptId::YtMusicInfo,
-        ScriptId::YtPlayerState,
-        ScriptId::YtQueueController,
-        ScriptId::YtMusicObserver,
-        ScriptId::YtMusicController,
-        ScriptId::YtPlayerListener,
-        ScriptId::YtDebug,
-        ScriptId::ServerControl,
-        ScriptId::YtMusicSearch,
-    ];
-}
+// src-tauri/src/scripts/mod.rs
+
+pub mod health;
+#[cfg(debug_assertions)]
+pub mod hot_reload;
+pub mod injector;
+pub mod plugins;
+pub mod settings;
+
+use std::borrow::Cow;
+
+/// Identificador único para cada script que inyectamos
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScriptId {
+    // Core
+    Logger,
+    Adblock,
+    TauriBridge,
+
+    // Providers
+    YtMusicInfo,
+    YtPlayerState,
+    YtQueueController,
+
+    YtMusicObserver,
+    YtPlayerListener,
+
+    YtMusicController,
+    YtMusicSearch,
+
+    // Debug
+    YtDebug,
+
+    ServerControl,
+}
+
+impl ScriptId {
+    /// Contenido del script. En debug se lee del disco si existe el directorio
+    /// de scripts (ver `hot_reload`), si no se usa la copia embebida.
+    pub fn content(self) -> Cow<'static, str> {
+        #[cfg(debug_assertions)]
+        if let Some(source) = hot_reload::read_from_disk(self) {
+            return Cow::Owned(source);
+        }
+        Cow::Borrowed(self.embedded())
+    }
+
+    pub const fn embedded(self) -> &'static str {
+        match self {
+            ScriptId::Logger => include_str!("./logger.js"),
+            ScriptId::Adblock => include_str!("./adblock.js"),
+            ScriptId::YtMusicInfo => include_str!("./providers/songinfo.js"),
+            ScriptId::TauriBridge => include_str!("./bridge.js"),
+            ScriptId::YtPlayerState => include_str!("./providers/playerstate.js"),
+            ScriptId::YtQueueController => include_str!("./providers/queuecontroller.js"),
+            ScriptId::YtMusicObserver => include_str!("./providers/observer.js"),
+            ScriptId::YtPlayerListener => include_str!("./providers/playerListeners.js"),
+            ScriptId::YtMusicController => include_str!("./providers/controller.js"),
+            ScriptId::YtDebug => include_str!("./providers/debug.js"),
+            ScriptId::ServerControl => include_str!("./server_control.js"),
+            ScriptId::YtMusicSearch => include_str!("./providers/search.js"),
+        }
+    }
+
+    /// Ruta relativa a `src/scripts`, igual que en `embedded`
+    pub const fn relative_path(self) -> &'static str {
+        match self {
+            ScriptId::Logger => "logger.js",
+            ScriptId::Adblock => "adblock.js",
+            ScriptId::YtMusicInfo => "providers/songinfo.js",
+            ScriptId::TauriBridge => "bridge.js",
+            ScriptId::YtPlayerState => "providers/playerstate.js",
+            ScriptId::YtQueueController => "providers/queuecontroller.js",
+            ScriptId::YtMusicObserver => "providers/observer.js",
+            ScriptId::YtPlayerListener => "providers/playerListeners.js",
+            ScriptId::YtMusicController => "providers/controller.js",
+            ScriptId::YtDebug => "providers/debug.js",
+            ScriptId::ServerControl => "server_control.js",
+            ScriptId::YtMusicSearch => "providers/search.js",
+        }
+    }
+
+    /// Clave estable usada para persistir el estado y en los comandos
+    pub const fn key(self) -> &'static str {
+        match self {
+            ScriptId::Logger => "logger",
+            ScriptId::Adblock => "adblock",
+            ScriptId::TauriBridge => "tauri_bridge",
+            ScriptId::YtMusicInfo => "yt_music_info",
+            ScriptId::YtPlayerState => "yt_player_state",
+            ScriptId::YtQueueController => "yt_queue_controller",
+            ScriptId::YtMusicObserver => "yt_music_observer",
+            ScriptId::YtPlayerListener => "yt_player_listener",
+            ScriptId::YtMusicController => "yt_music_controller",
+            ScriptId::YtMusicSearch => "yt_music_search",
+            ScriptId::YtDebug => "yt_debug",
+            ScriptId::ServerControl => "server_control",
+        }
+    }
+
+    pub fn from_key(key: &str) -> Option<ScriptId> {
+        Self::ALL_IN_ORDER.into_iter().find(|id| id.key() == key)
+    }
+
+    /// Scripts que deben ejecutarse antes (globals de `window` que usa este script)
+    pub const fn dependencies(self) -> &'static [ScriptId] {
+        match self {
+            ScriptId::YtMusicObserver => &[
+                ScriptId::Logger,
+                ScriptId::TauriBridge,
+                ScriptId::YtMusicInfo,
+            ],
+            ScriptId::YtPlayerListener => &[ScriptId::TauriBridge],
+            ScriptId::YtMusicController => &[ScriptId::YtMusicInfo],
+            _ => &[],
+        }
+    }
+
+    /// Scripts de los que dependen otros; no se pueden deshabilitar.
+    pub fn is_required(self) -> bool {
+        Self::ALL_IN_ORDER
+            .iter()
+            .any(|other| other.dependencies().contains(&self))
+    }
+
+    /// Orden de inyección para los scripts habilitados: respeta las dependencias
+    /// y descarta (avisando en el log) los scripts cuyas dependencias están
+    /// deshabilitadas o forman un ciclo.
+    pub fn resolve_order(is_enabled: impl Fn(ScriptId) -> bool) -> Vec<ScriptId> {
+        let enabled: Vec<ScriptId> = Self::ALL_IN_ORDER
+            .into_iter()
+            .filter(|id| is_enabled(*id))
+            .collect();
+        let (active, dropped) = order_by_dependencies(enabled, |id| id.dependencies().to_vec());
+
+        for id in dropped {
+            let missing: Vec<String> = id
+                .dependencies()
+                .iter()
+                .filter(|dep| !active.contains(dep))
+                .map(|dep| {
+                    if is_enabled(*dep) {
+                        format!("{} (not injectable)", dep.key())
+                    } else {
+                        format!("{} (disabled)", dep.key())
+                    }
+                })
+                .collect();
+            eprintln!(
+                "⚠️ Script '{}' skipped, missing dependencies: {}",
+                id.key(),
+                missing.join(", ")
+            );
+        }
+
+        active
+    }
+
+    pub const ALL_IN_ORDER: [ScriptId; 12] = [
+        ScriptId::Logger,
+        ScriptId::Adblock,
+        ScriptId::TauriBridge,
+        ScriptId::YtMusicInfo,
+        ScriptId::YtPlayerState,
+        ScriptId::YtQueueController,
+        ScriptId::YtMusicObserver,
+        ScriptId::YtMusicController,
+        ScriptId::YtPlayerListener,
+        ScriptId::YtDebug,
+        ScriptId::ServerControl,
+        ScriptId::YtMusicSearch,
+    ];
+}
+
+/// Ordena `pending` para que cada elemento vaya detrás de sus dependencias,
+/// conservando el orden original cuando no hay restricciones. Devuelve
+/// `(ordenados, descartados)`; se descartan los que dependen de algo que no
+/// está en `pending` o que forman un ciclo.
+fn order_by_dependencies<T: Copy + PartialEq>(
+    mut pending: Vec<T>,
+    dependencies: impl Fn(T) -> Vec<T>,
+) -> (Vec<T>, Vec<T>) {
+    let mut active: Vec<T> = Vec::new();
+
+    // Cada pasada coloca los elementos cuyas dependencias ya están colocadas
+    loop {
+        let before = pending.len();
+        pending.retain(|id| {
+            if dependencies(*id).iter().all(|dep| active.contains(dep)) {
+                active.push(*id);
+                false
+            } else {
+                true
+            }
+        });
+        if pending.is_empty() || pending.len() == before {
+            break;
+        }
+    }
+
+    (active, pending)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn all_enabled_keeps_every_script_after_its_dependencies() {
+        let order = ScriptId::resolve_order(|_| true);
+        assert_eq!(order.len(), ScriptId::ALL_IN_ORDER.len());
+        for (position, id) in order.iter().enumerate() {
+            for dep in id.dependencies() {
+                let dep_position = order.iter().position(|o| o == dep).unwrap();
+                assert!(dep_position < position, "{:?} before {:?}", dep, id);
+            }
+        }
+    }
+
+    #[test]
+    fn disabled_dependency_drops_its_dependents() {
+        let order = ScriptId::resolve_order(|id| id != ScriptId::YtMusicInfo);
+        assert!(!order.contains(&ScriptId::YtMusicInfo));
+        assert!(!order.contains(&ScriptId::YtMusicObserver));
+        assert!(!order.contains(&ScriptId::YtMusicController));
+        assert!(order.contains(&ScriptId::YtPlayerListener));
+        assert_eq!(order.len(), ScriptId::ALL_IN_ORDER.len() - 3);
+    }
+
+    #[test]
+    fn dependencies_move_ahead_of_earlier_dependents() {
+        let deps = |n: u8| if n == 1 { vec![3] } else { vec![] };
+        let (order, dropped) = order_by_dependencies(vec![1, 2, 3], deps);
+        assert_eq!(order, [2, 3, 1]);
+        assert!(dropped.is_empty());
+    }
+
+    #[test]
+    fn missing_dependency_is_dropped() {
+        let deps = |n: u8| if n == 2 { vec![9] } else { vec![] };
+        let (order, dropped) = order_by_dependencies(vec![1, 2, 3], deps);
+        assert_eq!(order, [1, 3]);
+        assert_eq!(dropped, [2]);
+    }
+
+    #[test]
+    fn cycle_is_dropped_without_looping() {
+        let deps = |n: u8| match n {
+            1 => vec![2],
+            2 => vec![1],
+            4 => vec![1],
+            _ => vec![],
+        };
+        let (order, dropped) = order_by_dependencies(vec![1, 2, 3, 4], deps);
+        assert_eq!(order, [3]);
+        assert_eq!(dropped, [1, 2, 4]);
+    }
+}
@@ -0,0 +1,430 @@
+// src-tauri/src/scripts/plugins.rs
+
+//! User plugins: folders under `<app_data_dir>/plugins/<id>/` with a `manifest.json`,
+//! injected after the built-in `ScriptId::ALL_IN_ORDER` scripts.
+//! Each script runs inside its own function scope, so plugins share state through `window`.
+//! Plugins run with the same access as the built-in scripts; there is no sandbox.
+//! The declared `permissions` are validated and reported, not enforced.
+
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+pub const PLUGINS_DIR: &str = "plugins";
+const MANIFEST_FILE: &str = "manifest.json";
+const DEFAULT_LOAD_ORDER: i32 = 100;
+
+/// Permissions a plugin may declare. They document what the plugin touches
+/// (`window.YTM`, `Pear.Bridge`, ...) and are shown to the user.
+pub const KNOWN_PERMISSIONS: &[&str] = &["player", "queue", "telemetry", "network", "storage"];
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct PluginManifest {
+    pub id: String,
+    pub version: String,
+    #[serde(default)]
+    pub description: String,
+    pub scripts: Vec<String>,
+    #[serde(default)]
+    pub permissions: Vec<String>,
+    #[serde(default = "default_load_order")]
+    pub load_order: i32,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+fn default_load_order() -> i32 {
+    DEFAULT_LOAD_ORDER
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum PluginStatus {
+    Loaded,
+    Disabled,
+    Invalid { errors: Vec<String> },
+    Failed { error: String },
+}
+
+/// Everything known about one plugin folder, valid or not.
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginReport {
+    pub dir: PathBuf,
+    pub manifest: Option<PluginManifest>,
+    pub status: PluginStatus,
+    /// Exceptions the page reported this session; they do not change `status`.
+    pub runtime_errors: u32,
+    pub last_error: Option<String>,
+    #[serde(skip)]
+    sources: Vec<(String, String)>,
+}
+
+impl PluginReport {
+    pub fn id(&self) -> String {
+        self.manifest
+            .as_ref()
+            .map(|m| m.id.clone())
+            .unwrap_or_else(|| folder_name(&self.dir))
+    }
+
+    fn load_order(&self) -> i32 {
+        self.manifest
+            .as_ref()
+            .map(|m| m.load_order)
+            .unwrap_or(DEFAULT_LOAD_ORDER)
+    }
+}
+
+#[derive(Default)]
+pub struct PluginRegistry {
+    plugins: Mutex<Vec<PluginReport>>,
+}
+
+impl PluginRegistry {
+    /// Rescans `root` and replaces the known plugins.
+    pub fn discover(&self, root: &Path) {
+        let mut plugins = discover_plugins(root);
+        plugins.sort_by(|a, b| {
+            a.load_order()
+                .cmp(&b.load_order())
+                .then_with(|| a.id().cmp(&b.id()))
+        });
+
+        for plugin in &plugins {
+            match &plugin.status {
+                PluginStatus::Loaded => println!("🧩 Plugin loaded: {}", plugin.id()),
+                PluginStatus::Invalid { errors } => {
+                    eprintln!("❌ Plugin [{}] invalid: {}", plugin.id(), errors.join("; "))
+                }
+                _ => {}
+            }
+        }
+
+        *self.plugins.lock().unwrap() = plugins;
+    }
+
+    pub fn reports(&self) -> Vec<PluginReport> {
+        self.plugins.lock().unwrap().clone()
+    }
+
+    /// Scripts of enabled, valid plugins in load order, wrapped so a throwing
+    /// plugin is reported back instead of aborting the rest of the injection.
+    /// Returns `(plugin id, file, script)`.
+    pub fn injectable_scripts(&self) -> Vec<(String, String, String)> {
+        self.plugins
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|p| matches!(p.status, PluginStatus::Loaded))
+            .flat_map(|p| {
+                let id = p.id();
+                p.sources.iter().map(move |(file, source)| {
+                    (id.clone(), file.clone(), wrap_script(&id, file, source))
+                })
+            })
+            .collect()
+    }
+
+    /// The webview refused the script, so it never ran.
+    pub fn mark_failed(&self, id: &str, error: String) {
+        let mut plugins = self.plugins.lock().unwrap();
+        if let Some(plugin) = plugins.iter_mut().find(|p| p.id() == id) {
+            eprintln!("❌ Plugin [{}] failed: {}", id, error);
+            plugin.status = PluginStatus::Failed { error };
+        }
+    }
+
+    /// Counts an exception thrown by a plugin script. Page code can report
+    /// these, so they are informational and the plugin keeps being injected.
+    pub fn record_runtime_error(&self, id: &str, error: String) {
+        let mut plugins = self.plugins.lock().unwrap();
+        if let Some(plugin) = plugins.iter_mut().find(|p| p.id() == id) {
+            eprintln!("⚠️ Plugin [{}] threw: {}", id, error);
+            plugin.runtime_errors = plugin.runtime_errors.saturating_add(1);
+            plugin.last_error = Some(error);
+        }
+    }
+}
+
+fn discover_plugins(root: &Path) -> Vec<PluginReport> {
+    let Ok(entries) = fs::read_dir(root) else {
+        return Vec::new();
+    };
+
+    entries
+        .flatten()
+        .map(|entry| entry.path())
+        .filter(|path| path.is_dir())
+        .map(|dir| load_plugin(&dir))
+        .collect()
+}
+
+fn load_plugin(dir: &Path) -> PluginReport {
+    let invalid = |errors: Vec<String>, manifest: Option<PluginManifest>| PluginReport {
+        dir: dir.to_path_buf(),
+        manifest,
+        status: PluginStatus::Invalid { errors },
+        runtime_errors: 0,
+        last_error: None,
+        sources: Vec::new(),
+    };
+
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let raw = match fs::read_to_string(&manifest_path) {
+        Ok(raw) => raw,
+        Err(e) => return invalid(vec![format!("Cannot read {}: {}", MANIFEST_FILE, e)], None),
+    };
+    let manifest: PluginManifest = match serde_json::from_str(&raw) {
+        Ok(m) => m,
+        Err(e) => return invalid(vec![format!("Invalid {}: {}", MANIFEST_FILE, e)], None),
+    };
+
+    let mut errors = validate_manifest(&manifest, &folder_name(dir));
+    let mut sources = Vec::new();
+    for file in &manifest.scripts {
+        if !is_safe_relative_path(file) {
+            continue; // already reported by validate_manifest
+        }
+        match fs::read_to_string(dir.join(file)) {
+            Ok(source) => sources.push((file.clone(), source)),
+            Err(e) => errors.push(format!("Cannot read script '{}': {}", file, e)),
+        }
+    }
+
+    if !errors.is_empty() {
+        return invalid(errors, Some(manifest));
+    }
+
+    let status = if manifest.enabled {
+        PluginStatus::Loaded
+    } else {
+        PluginStatus::Disabled
+    };
+    PluginReport {
+        dir: dir.to_path_buf(),
+        manifest: Some(manifest),
+        status,
+        runtime_errors: 0,
+        last_error: None,
+        sources,
+    }
+}
+
+fn validate_manifest(manifest: &PluginManifest, folder: &str) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if manifest.id.is_empty()
+        || !manifest
+            .id
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_')
+    {
+        errors.push(format!(
+            "id '{}' must be non-empty and use only a-z, 0-9, '-' or '_'",
+            manifest.id
+        ));
+    } else if manifest.id != folder {
+        errors.push(format!(
+            "id '{}' does not match folder name '{}'",
+            manifest.id, folder
+        ));
+    }
+
+    if manifest.version.trim().is_empty() {
+        errors.push("version must not be empty".to_string());
+    }
+
+    if manifest.scripts.is_empty() {
+        errors.push("scripts must list at least one file".to_string());
+    }
+    for file in &manifest.scripts {
+        if !is_safe_relative_path(file) {
+            errors.push(format!(
+                "script path '{}' must stay inside the plugin folder",
+                file
+            ));
+        } else if !file.ends_with(".js") {
+            errors.push(format!("script '{}' is not a .js file", file));
+        }
+    }
+
+    for permission in &manifest.permissions {
+        if !KNOWN_PERMISSIONS.contains(&permission.as_str()) {
+            errors.push(format!("unknown permission '{}'", permission));
+        }
+    }
+
+    errors
+}
+
+fn is_safe_relative_path(file: &str) -> bool {
+    let path = Path::new(file);
+    !file.is_empty()
+        && path
+            .components()
+            .all(|c| matches!(c, Component::Normal(_) | Component::CurDir))
+}
+
+fn folder_name(dir: &Path) -> String {
+    dir.file_name()
+        .map(|n| n.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+/// Key of a plugin script in the injection guard and in `ScriptHealth`.
+pub fn script_key(id: &str, file: &str) -> String {
+    format!("plugin:{}/{}", id, file)
+}
+
+/// A throwing plugin marks its script unhealthy for the current load only and
+/// bumps the plugin's error count.
+fn wrap_script(id: &str, file: &str, source: &str) -> String {
+    let id_json = serde_json::to_string(id).unwrap_or_default();
+    let file_json = serde_json::to_string(file).unwrap_or_default();
+    let key_json = serde_json::to_string(&script_key(id, file)).unwrap_or_default();
+    format!(
+        r#"(function() {{
+try {{
+{source}
+}} catch (e) {{
+    const message = {file_json} + ": " + (e && e.message || String(e));
+    console.error("❌ Plugin [" + {id_json} + "] " + {file_json} + ":", e);
+    window.Pear?.Health?.report({key_json}, false, message);
+    window.__TAURI__?.core?.invoke('report_plugin_error', {{ id: {id_json}, error: message }});
+}}
+}})();"#
+    )
+}
+
+/// Scans the plugin folder in the app data dir, creating it on first run.
+pub fn discover_in_app_data(app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let root = app.path().app_data_dir()?.join(PLUGINS_DIR);
+    fs::create_dir_all(&root)?;
+    app.state::<PluginRegistry>().discover(&root);
+    Ok(())
+}
+
+/// Rescans the plugin folder; the new set is injected on the next page load.
+pub fn reload_plugins(app: &AppHandle) -> Result<Vec<PluginReport>, String> {
+    discover_in_app_data(app).map_err(|e| e.to_string())?;
+    Ok(app.state::<PluginRegistry>().reports())
+}
+
+#[tauri::command]
+pub fn report_plugin_error(registry: tauri::State<'_, PluginRegistry>, id: String, error: String) {
+    registry.record_runtime_error(&id, error);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(id: &str, scripts: &[&str]) -> PluginManifest {
+        PluginManifest {
+            id: id.to_string(),
+            version: "1.0.0".to_string(),
+            description: String::new(),
+            scripts: scripts.iter().map(|s| s.to_string()).collect(),
+            permissions: vec!["player".to_string()],
+            load_order: DEFAULT_LOAD_ORDER,
+            enabled: true,
+        }
+    }
+
+    #[test]
+    fn valid_manifest_has_no_errors() {
+        let errors = validate_manifest(
+            &manifest("lyrics-pane", &["main.js", "./ui/panel.js"]),
+            "lyrics-pane",
+        );
+        assert!(errors.is_empty(), "{:?}", errors);
+    }
+
+    #[test]
+    fn id_must_be_slug_and_match_folder() {
+        assert_eq!(
+            validate_manifest(&manifest("Bad Id", &["a.js"]), "Bad Id").len(),
+            1
+        );
+        assert_eq!(validate_manifest(&manifest("", &["a.js"]), "").len(), 1);
+        let errors = validate_manifest(&manifest("one", &["a.js"]), "two");
+        assert_eq!(errors, ["id 'one' does not match folder name 'two'"]);
+    }
+
+    #[test]
+    fn scripts_must_be_js_inside_the_folder() {
+        let errors = validate_manifest(
+            &manifest("p", &["../escape.js", "/etc/passwd.js", "style.css"]),
+            "p",
+        );
+        assert_eq!(errors.len(), 3, "{:?}", errors);
+
+        let mut empty = manifest("p", &[]);
+        empty.version = " ".to_string();
+        assert_eq!(validate_manifest(&empty, "p").len(), 2);
+    }
+
+    #[test]
+    fn permissions_must_be_known() {
+        let mut declared = manifest("p", &["a.js"]);
+        declared.permissions = vec!["queue".to_string(), "filesystem".to_string()];
+        assert_eq!(
+            validate_manifest(&declared, "p"),
+            ["unknown permission 'filesystem'"]
+        );
+    }
+
+    #[test]
+    fn optional_manifest_fields_have_defaults() {
+        let parsed: PluginManifest =
+            serde_json::from_str(r#"{"id": "p", "version": "1", "scripts": ["a.js"]}"#).unwrap();
+        assert!(parsed.enabled);
+        assert!(parsed.permissions.is_empty());
+        assert_eq!(parsed.load_order, DEFAULT_LOAD_ORDER);
+    }
+
+    #[test]
+    fn reports_carry_the_declared_permissions() {
+        let report = PluginReport {
+            dir: PathBuf::from("p"),
+            manifest: Some(manifest("p", &["a.js"])),
+            status: PluginStatus::Loaded,
+            runtime_errors: 0,
+            last_error: None,
+            sources: Vec::new(),
+        };
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json["manifest"]["permissions"],
+            serde_json::json!(["player"])
+        );
+    }
+
+    #[test]
+    fn runtime_errors_do_not_disable_the_plugin() {
+        let registry = PluginRegistry::default();
+        registry.plugins.lock().unwrap().push(PluginReport {
+            dir: PathBuf::from("p"),
+            manifest: Some(manifest("p", &["a.js"])),
+            status: PluginStatus::Loaded,
+            runtime_errors: 0,
+            last_error: None,
+            sources: vec![("a.js".to_string(), "throw 1;".to_string())],
+        });
+
+        registry.record_runtime_error("p", "a.js: boom".to_string());
+        registry.record_runtime_error("p", "a.js: boom again".to_string());
+
+        let report = &registry.reports()[0];
+        assert!(matches!(report.status, PluginStatus::Loaded));
+        assert_eq!(report.runtime_errors, 2);
+        assert_eq!(report.last_error.as_deref(), Some("a.js: boom again"));
+        assert_eq!(registry.injectable_scripts().len(), 1);
+    }
+}
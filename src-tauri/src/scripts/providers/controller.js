@@ -22,11 +22,17 @@ class YouTubeMusicControls {
         this.playerBar = document.querySelector("ytmusic-player-bar");
 
         if (this.video && this.playerBar) {
+          window.Pear?.Health?.report("yt_music_controller", true);
           resolve();
         } else {
           // Timeout de 10 segundos para no quedarse en loop infinito
           if (attempts > 20) {
             console.warn("YTM Controls: No se encontró el reproductor.");
+            window.Pear?.Health?.report(
+              "yt_music_controller",
+              false,
+              `Not found: ${[!this.video && "video", !this.playerBar && "ytmusic-player-bar"].filter(Boolean).join(", ")}`,
+            );
             resolve(); // Resolvemos igual para no romper la app, pero sin video
             return;
           }
@@ -106,9 +106,11 @@ const YTMActionSimulator = {
     // Aquí podrías iterar para ver si alguno tiene texto "Borrar" o "Remove"
   },
 };
-// Uso:
+// Uso: el inyector ejecuta cada script en su propia función, así que se
+// exponen en window para poder llamarlos desde la consola
 window.debugUtils = {
   YTMQueueInspector,
   YTMDataSpy,
   YTMActionSimulator,
 };
+Object.assign(window, window.debugUtils);
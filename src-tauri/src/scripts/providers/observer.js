@@ -194,6 +194,7 @@ if (window.YTM.Observer && typeof window.YTM.Observer.stop === 'function') {
 }
 
 if (location.hostname.includes("music.youtube.com")) {
+    let waitAttempts = 0;
     const waitForApp = setInterval(() => {
         if (document.querySelector("ytmusic-player-bar") && document.querySelector("video")) {
             clearInterval(waitForApp);
@@ -201,6 +202,10 @@ if (location.hostname.includes("music.youtube.com")) {
             // Instanciamos y guardamos en el namespace
             window.YTM.Observer = new YouTubeMusicObserver();
             window.YTM.Observer.start();
+            window.Pear?.Health?.report("yt_music_observer", true);
+        } else if (++waitAttempts === 40) {
+            // Seguimos esperando, pero avisamos que tarda demasiado
+            window.Pear?.Health?.report("yt_music_observer", false, "ytmusic-player-bar or video not found after 20s");
         }
     }, 500);
 }
\ No newline at end of file
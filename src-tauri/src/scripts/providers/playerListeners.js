@@ -69,11 +69,11 @@ class YouTubeMusicListeners {
   }
 
   observePlayerBarState() {
-    const waitForBar = setInterval(() => {
+    this.waitForBar = setInterval(() => {
       const playerBar = document.querySelector("ytmusic-player-bar");
       if (!playerBar) return;
 
-      clearInterval(waitForBar);
+      clearInterval(this.waitForBar);
 
       const observer = new MutationObserver(() => {
         // Lógica de detección (basada en tu código anterior)
@@ -125,6 +125,7 @@ class YouTubeMusicListeners {
   }
 
   destroy() {
+    clearInterval(this.waitForBar);
     this.observers.forEach((obs) => obs.disconnect());
     if (this.currentVideo) {
       this.currentVideo.removeEventListener("timeupdate", this.videoHandlers.time);
@@ -137,5 +138,12 @@ class YouTubeMusicListeners {
 
 // Inicialización
 window.YTM = window.YTM || {};
+
+// Detener instancia anterior si existe (útil para Hot Reloading)
+if (window.YTM.Events && typeof window.YTM.Events.destroy === 'function') {
+    window.YTM.Events.destroy();
+}
+
+// time-update, volume-change y state-change solo salen de aquí (letras, overlay, MQTT)
 window.YTM.Events = new YouTubeMusicListeners();
-// window.YTM.Events.init(); // Descomentar si quieres auto-iniciar o iniciarlo desde bridge.js
\ No newline at end of file
+window.YTM.Events.init();
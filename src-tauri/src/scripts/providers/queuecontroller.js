@@ -164,3 +164,20 @@ class YouTubeMusicQueueController {
 
 window.YTM = window.YTM || {};
 window.YTM.Queue = new YouTubeMusicQueueController();
+
+// Diagnóstico: avisar a Rust si la cola aparece (o no) en el DOM
+(() => {
+  let attempts = 0;
+  const check = () => {
+    if (document.querySelector("#queue") && document.querySelector("ytmusic-app")) {
+      window.Pear?.Health?.report("yt_queue_controller", window.YTM.Queue.init());
+      return;
+    }
+    if (++attempts > 40) {
+      window.Pear?.Health?.report("yt_queue_controller", false, "#queue or ytmusic-app not found");
+      return;
+    }
+    setTimeout(check, 500);
+  };
+  check();
+})();
@@ -0,0 +1,209 @@
+// src-tauri/src/scripts/settings.rs
+
+//! Persisted enabled/disabled flags for the built-in scripts.
+
+use super::ScriptId;
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+
+const SETTINGS_FILE: &str = "scripts.json";
+
+#[derive(Default, Serialize, Deserialize)]
+struct StoredSettings {
+    #[serde(default)]
+    disabled: Vec<String>,
+}
+
+#[derive(Default)]
+pub struct ScriptSettings {
+    disabled: Mutex<HashSet<ScriptId>>,
+    path: Mutex<Option<PathBuf>>,
+}
+
+#[derive(Serialize)]
+pub struct ScriptInfo {
+    id: &'static str,
+    enabled: bool,
+    /// Enabled and all dependencies active, i.e. it will be injected.
+    active: bool,
+    /// Other scripts depend on it, so it cannot be disabled.
+    required: bool,
+    dependencies: Vec<&'static str>,
+}
+
+impl ScriptSettings {
+    pub fn load(&self, app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = app.path().app_data_dir()?;
+        fs::create_dir_all(&dir)?;
+        self.load_from(dir.join(SETTINGS_FILE))
+    }
+
+    fn load_from(&self, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        *self.path.lock().unwrap() = Some(path.clone());
+
+        if let Ok(raw) = fs::read_to_string(&path) {
+            let stored: StoredSettings = match serde_json::from_str(&raw) {
+                Ok(stored) => stored,
+                Err(e) => return Err(backup(&path, e).into()),
+            };
+            let mut disabled = self.disabled.lock().unwrap();
+            for key in stored.disabled {
+                match ScriptId::from_key(&key) {
+                    Some(id) if id.is_required() => {
+                        eprintln!("⚠️ Script {} is required, keeping it enabled", key)
+                    }
+                    Some(id) => {
+                        disabled.insert(id);
+                    }
+                    None => eprintln!("⚠️ Unknown script in {}: {}", SETTINGS_FILE, key),
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn is_enabled(&self, id: ScriptId) -> bool {
+        !self.disabled.lock().unwrap().contains(&id)
+    }
+
+    /// Enabled scripts in a valid injection order.
+    pub fn injection_order(&self) -> Vec<ScriptId> {
+        let disabled = self.disabled.lock().unwrap().clone();
+        ScriptId::resolve_order(|id| !disabled.contains(&id))
+    }
+
+    pub fn list(&self) -> Vec<ScriptInfo> {
+        let order = self.injection_order();
+        ScriptId::ALL_IN_ORDER
+            .into_iter()
+            .map(|id| ScriptInfo {
+                id: id.key(),
+                enabled: self.is_enabled(id),
+                active: order.contains(&id),
+                required: id.is_required(),
+                dependencies: id.dependencies().iter().map(|d| d.key()).collect(),
+            })
+            .collect()
+    }
+
+    pub fn set_enabled(&self, id: ScriptId, enabled: bool) -> Result<(), String> {
+        if !enabled && id.is_required() {
+            return Err(format!(
+                "Script {} is required by other scripts and cannot be disabled",
+                id.key()
+            ));
+        }
+        {
+            let mut disabled = self.disabled.lock().unwrap();
+            if enabled {
+                disabled.remove(&id);
+            } else {
+                disabled.insert(id);
+            }
+        }
+        self.save()
+    }
+
+    fn save(&self) -> Result<(), String> {
+        let Some(path) = self.path.lock().unwrap().clone() else {
+            return Ok(());
+        };
+        let mut keys: Vec<String> = self
+            .disabled
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|id| id.key().to_string())
+            .collect();
+        keys.sort();
+
+        let data = serde_json::to_string_pretty(&StoredSettings { disabled: keys })
+            .map_err(|e| e.to_string())?;
+        fs::write(&path, data).map_err(|e| e.to_string())
+    }
+}
+
+/// Keeps the user's file; the next save writes the current flags next to it.
+fn backup(path: &Path, error: serde_json::Error) -> String {
+    let backup = path.with_extension("json.bak");
+    match fs::rename(path, &backup) {
+        Ok(()) => format!("{} (moved to {})", error, backup.display()),
+        Err(e) => format!("{} (backup failed: {})", error, e),
+    }
+}
+
+/// Persists the flag and reloads the main page so the new script set is injected.
+/// Only reachable from the local API; the player page must not toggle scripts.
+pub fn set_script_enabled(
+    app: &AppHandle,
+    id: ScriptId,
+    enabled: bool,
+) -> Result<Vec<ScriptInfo>, String> {
+    let settings = app.state::<ScriptSettings>();
+    if settings.is_enabled(id) != enabled {
+        settings.set_enabled(id, enabled)?;
+        println!(
+            "🔁 Script {} {}, reloading page",
+            id.key(),
+            if enabled { "enabled" } else { "disabled" }
+        );
+        if let Some(window) = app.get_webview_window(crate::MAIN_WINDOW_LABEL) {
+            window
+                .eval("window.location.reload();")
+                .map_err(|e| e.to_string())?;
+        }
+    }
+    Ok(settings.list())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_settings(name: &str, content: Option<&str>) -> (PathBuf, ScriptSettings) {
+        let dir = std::env::temp_dir().join(format!("yt-hear-{}-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(SETTINGS_FILE);
+        if let Some(content) = content {
+            fs::write(&path, content).unwrap();
+        }
+        (path, ScriptSettings::default())
+    }
+
+    #[test]
+    fn unreadable_file_is_backed_up_before_saving() {
+        let (path, settings) = temp_settings("scripts-corrupt", Some(r#"{"disabled": ["#));
+        assert!(settings.load_from(path.clone()).is_err());
+
+        settings.set_enabled(ScriptId::YtDebug, false).unwrap();
+        let backup = fs::read_to_string(path.with_extension("json.bak")).unwrap();
+        assert_eq!(backup, r#"{"disabled": ["#);
+        let saved: StoredSettings =
+            serde_json::from_str(&fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(saved.disabled, ["yt_debug"]);
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn required_scripts_stay_enabled() {
+        let (path, settings) = temp_settings(
+            "scripts-required",
+            Some(r#"{"disabled": ["tauri_bridge", "yt_debug"]}"#),
+        );
+        settings.load_from(path.clone()).unwrap();
+        assert!(settings.is_enabled(ScriptId::TauriBridge));
+        assert!(!settings.is_enabled(ScriptId::YtDebug));
+
+        assert!(settings.set_enabled(ScriptId::YtMusicInfo, false).is_err());
+        assert!(settings.is_enabled(ScriptId::YtMusicInfo));
+        assert!(settings
+            .injection_order()
+            .contains(&ScriptId::YtMusicObserver));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+}
@@ -0,0 +1,190 @@
+// src-tauri/src/shutdown.rs
+
+//! Orderly shutdown: stop the API server, fail in-flight bridge requests,
+//! run the subsystem hooks (each under a timeout) and exit through Tauri.
+
+use crate::bridge::AppState;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+const SERVER_STOP_TIMEOUT_SECS: u64 = 5;
+const HOOK_TIMEOUT_SECS: u64 = 3;
+
+type ShutdownHook = Box<dyn FnOnce() -> Result<(), String> + Send>;
+
+/// Registry of subsystem shutdown hooks. Hooks are blocking and run in
+/// registration order on a blocking thread.
+#[derive(Default)]
+pub struct ShutdownCoordinator {
+    started: AtomicBool,
+    hooks: Mutex<Vec<(String, ShutdownHook)>>,
+}
+
+impl ShutdownCoordinator {
+    pub fn register(
+        &self,
+        name: impl Into<String>,
+        hook: impl FnOnce() -> Result<(), String> + Send + 'static,
+    ) {
+        self.hooks
+            .lock()
+            .unwrap()
+            .push((name.into(), Box::new(hook)));
+    }
+
+    /// Whether a shutdown is already running; the final exit request must go through.
+    pub fn is_started(&self) -> bool {
+        self.started.load(Ordering::SeqCst)
+    }
+
+    fn take_hooks(&self) -> Vec<(String, ShutdownHook)> {
+        std::mem::take(&mut *self.hooks.lock().unwrap())
+    }
+}
+
+/// Starts the shutdown sequence in the background. Calling it again while
+/// it runs does nothing.
+pub fn request(app: &AppHandle) {
+    let coordinator = app.state::<ShutdownCoordinator>();
+    if coordinator.started.swap(true, Ordering::SeqCst) {
+        return;
+    }
+    crate::window::mark_quitting();
+
+    let app = app.clone();
+    tauri::async_runtime::spawn(async move {
+        run(&app).await;
+        println!("👋 Shutdown complete");
+        app.exit(0);
+    });
+}
+
+async fn run(app: &AppHandle) {
+    println!("🛑 Shutting down...");
+    let state = app.state::<Arc<AppState>>().inner().clone();
+
+    stop_http_server(&state).await;
+    fail_pending_requests(&state).await;
+
+    let hooks = app.state::<ShutdownCoordinator>().take_hooks();
+    run_hooks(hooks, Duration::from_secs(HOOK_TIMEOUT_SECS)).await;
+}
+
+/// Runs the hooks one after another; a hook that outlives `timeout` is left
+/// running on its thread and the sequence moves on.
+async fn run_hooks(
+    hooks: Vec<(String, ShutdownHook)>,
+    timeout: Duration,
+) -> Vec<(String, Result<(), String>)> {
+    let mut outcomes = Vec::with_capacity(hooks.len());
+    for (name, hook) in hooks {
+        let task = tokio::task::spawn_blocking(hook);
+        let outcome = match tokio::time::timeout(timeout, task).await {
+            Ok(Ok(Ok(()))) => {
+                println!("✅ Shutdown hook '{}' done", name);
+                Ok(())
+            }
+            Ok(Ok(Err(e))) => {
+                eprintln!("❌ Shutdown hook '{}' failed: {}", name, e);
+                Err(e)
+            }
+            Ok(Err(e)) => {
+                eprintln!("❌ Shutdown hook '{}' panicked: {}", name, e);
+                Err(e.to_string())
+            }
+            Err(_) => {
+                eprintln!("⚠️ Shutdown hook '{}' timed out after {:?}", name, timeout);
+                Err("timed out".to_string())
+            }
+        };
+        outcomes.push((name, outcome));
+    }
+    outcomes
+}
+
+async fn stop_http_server(state: &AppState) {
+    if crate::http_server::stop_server(state).await.is_err() {
+        return; // not running
+    }
+    let Some(task) = state.http_server_task.lock().await.take() else {
+        return;
+    };
+    if tokio::time::timeout(Duration::from_secs(SERVER_STOP_TIMEOUT_SECS), task)
+        .await
+        .is_err()
+    {
+        eprintln!(
+            "⚠️ HTTP Server did not stop within {}s",
+            SERVER_STOP_TIMEOUT_SECS
+        );
+    }
+}
+
+/// Dropping the senders makes every waiting `request_live_data` return an error.
+async fn fail_pending_requests(state: &AppState) {
+    let pending = std::mem::take(&mut *state.pending_requests.lock().await);
+    if !pending.is_empty() {
+        println!("🧹 Cancelling {} pending bridge requests", pending.len());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Instant;
+
+    fn recording(name: &str, log: &Arc<Mutex<Vec<String>>>) -> (String, ShutdownHook) {
+        let log = log.clone();
+        let entry = name.to_string();
+        (
+            name.to_string(),
+            Box::new(move || {
+                log.lock().unwrap().push(entry);
+                Ok(())
+            }),
+        )
+    }
+
+    #[tokio::test]
+    async fn hooks_run_in_registration_order() {
+        let coordinator = ShutdownCoordinator::default();
+        let log = Arc::new(Mutex::new(Vec::new()));
+        for name in ["mqtt", "adblock", "window-state"] {
+            let (name, hook) = recording(name, &log);
+            coordinator.register(name, hook);
+        }
+        coordinator.register("broken", || Err("disk full".to_string()));
+
+        let outcomes = run_hooks(coordinator.take_hooks(), Duration::from_secs(1)).await;
+        assert_eq!(*log.lock().unwrap(), ["mqtt", "adblock", "window-state"]);
+        assert_eq!(
+            outcomes[3],
+            ("broken".to_string(), Err("disk full".to_string()))
+        );
+        assert!(coordinator.take_hooks().is_empty());
+    }
+
+    #[tokio::test]
+    async fn hung_hook_times_out_and_later_hooks_still_run() {
+        let log = Arc::new(Mutex::new(Vec::new()));
+        let (release, hold) = mpsc::channel::<()>();
+        let hung: ShutdownHook = Box::new(move || {
+            let _ = hold.recv();
+            Ok(())
+        });
+        let hooks = vec![("hung".to_string(), hung), recording("after", &log)];
+
+        let started = Instant::now();
+        let outcomes = run_hooks(hooks, Duration::from_millis(100)).await;
+        assert!(started.elapsed() < Duration::from_secs(2));
+        assert_eq!(outcomes[0].1, Err("timed out".to_string()));
+        assert_eq!(outcomes[1].1, Ok(()));
+        assert_eq!(*log.lock().unwrap(), ["after"]);
+
+        // Let the blocking thread end so the test runtime can shut down
+        drop(release);
+    }
+}
@@ -0,0 +1,471 @@
+// src-tauri/src/single_instance.rs
+
+//! Single-instance guard over a Unix socket in `$XDG_RUNTIME_DIR` (or a
+//! per-user folder in the temp directory when it is unset).
+//!
+//! Whoever holds an exclusive `flock` on the lock file next to the socket is
+//! the primary instance and binds the socket. Later launches connect, send their
+//! command-line arguments as one JSON line and exit; the first instance then
+//! focuses its window and applies them (a `music.youtube.com` URL to open or
+//! `--action <name>` for a playback action).
+
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+
+const SOCKET_NAME: &str = "instance.sock";
+const LOCK_NAME: &str = "instance.lock";
+const MUSIC_HOST: &str = "music.youtube.com";
+/// How long either side waits for the other's line.
+const FORWARD_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(2);
+/// Pause between connection attempts while the primary has the lock but no socket yet.
+#[cfg(unix)]
+const CONNECT_RETRY: std::time::Duration = std::time::Duration::from_millis(50);
+
+/// Player actions that can be forwarded; they take no arguments in `bridge.js`
+/// (`goBack`/`goForward` need a number of seconds and are not listed).
+pub const FORWARDABLE_ACTIONS: &[&str] = &[
+    "play",
+    "pause",
+    "playPause",
+    "next",
+    "previous",
+    "toggleMute",
+    "like",
+    "dislike",
+    "toggleShuffle",
+    "switchRepeat",
+];
+
+/// Outcome of [`acquire`].
+pub enum Instance {
+    /// This process is the primary instance and owns the listener. The lock
+    /// file stays locked for as long as `lock` is open.
+    #[cfg(unix)]
+    Primary {
+        listener: std::os::unix::net::UnixListener,
+        lock: std::fs::File,
+    },
+    /// Another instance is running and received our arguments.
+    Forwarded,
+    /// Another instance holds the lock but did not take our arguments.
+    #[cfg(unix)]
+    Unresponsive,
+    /// No guard on this platform.
+    #[cfg(not(unix))]
+    Unguarded,
+}
+
+/// What a forwarded argument list asks for.
+#[derive(Debug, Default, PartialEq)]
+pub struct ForwardedArgs {
+    pub url: Option<url::Url>,
+    pub action: Option<String>,
+}
+
+impl ForwardedArgs {
+    pub fn parse(args: &[String]) -> Self {
+        let mut parsed = Self::default();
+        let mut iter = args.iter();
+        while let Some(arg) = iter.next() {
+            let action = match arg.strip_prefix("--action") {
+                Some("") => iter.next().map(String::as_str),
+                Some(rest) => rest.strip_prefix('='),
+                None => None,
+            };
+            if let Some(action) = action {
+                if FORWARDABLE_ACTIONS.contains(&action) {
+                    parsed.action = Some(action.to_string());
+                } else {
+                    eprintln!("⚠️ Unknown forwarded action: {}", action);
+                }
+                continue;
+            }
+
+            match url::Url::parse(arg) {
+                Ok(url) if url.scheme() == "https" && url.host_str() == Some(MUSIC_HOST) => {
+                    parsed.url = Some(url)
+                }
+                _ => {}
+            }
+        }
+        parsed
+    }
+}
+
+/// Per-user directory for the instance socket and the API discovery file:
+/// `$XDG_RUNTIME_DIR/yt-hear`, or `yt-hear-<uid>` in the shared temp folder.
+pub fn runtime_dir() -> std::path::PathBuf {
+    if let Some(dir) = std::env::var_os("XDG_RUNTIME_DIR") {
+        return std::path::PathBuf::from(dir).join(env!("CARGO_PKG_NAME"));
+    }
+    #[cfg(unix)]
+    let name = format!("{}-{}", env!("CARGO_PKG_NAME"), current_uid());
+    #[cfg(not(unix))]
+    let name = env!("CARGO_PKG_NAME").to_string();
+    std::env::temp_dir().join(name)
+}
+
+#[cfg(unix)]
+fn current_uid() -> u32 {
+    // SAFETY: getuid has no preconditions and cannot fail
+    unsafe { libc::getuid() }
+}
+
+/// Refuses a folder created by another user, or swapped for a symlink, in the
+/// shared temp folder: whoever owns it could take over the socket.
+#[cfg(unix)]
+fn check_owner(dir: &std::path::Path) -> std::io::Result<()> {
+    use std::os::unix::fs::MetadataExt;
+
+    let metadata = std::fs::symlink_metadata(dir)?;
+    if !metadata.is_dir() || metadata.uid() != current_uid() {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            format!("{} is not a folder owned by this user", dir.display()),
+        ));
+    }
+    Ok(())
+}
+
+/// Becomes the primary instance, or forwards `args` to the running one.
+/// Must run before the Tauri app is built so a second launch never opens a window.
+#[cfg(unix)]
+pub fn acquire(args: &[String]) -> Result<Instance, Box<dyn std::error::Error>> {
+    acquire_in(&runtime_dir(), args)
+}
+
+#[cfg(unix)]
+fn acquire_in(
+    dir: &std::path::Path,
+    args: &[String],
+) -> Result<Instance, Box<dyn std::error::Error>> {
+    use std::fs::{OpenOptions, Permissions, TryLockError};
+    use std::os::unix::fs::PermissionsExt;
+    use std::os::unix::net::UnixListener;
+
+    std::fs::create_dir_all(dir)?;
+    check_owner(dir)?;
+    std::fs::set_permissions(dir, Permissions::from_mode(0o700))?;
+
+    // The lock decides who is primary: launches racing past a dead socket
+    // would otherwise all remove it and bind their own
+    let lock = OpenOptions::new()
+        .create(true)
+        .write(true)
+        .truncate(false)
+        .open(dir.join(LOCK_NAME))?;
+    let path = dir.join(SOCKET_NAME);
+    match lock.try_lock() {
+        Ok(()) => {
+            // Whatever socket file is left belongs to a dead instance
+            let _ = std::fs::remove_file(&path);
+            let listener = UnixListener::bind(&path)?;
+            std::fs::set_permissions(&path, Permissions::from_mode(0o600))?;
+            Ok(Instance::Primary { listener, lock })
+        }
+        Err(TryLockError::WouldBlock) => match forward(&path, args) {
+            Ok(()) => {
+                println!("📨 yt-hear is already running, arguments forwarded");
+                Ok(Instance::Forwarded)
+            }
+            Err(e) => {
+                eprintln!("⚠️ yt-hear is already running but did not answer: {}", e);
+                Ok(Instance::Unresponsive)
+            }
+        },
+        Err(TryLockError::Error(e)) => Err(e.into()),
+    }
+}
+
+/// Sends `args` to the primary instance and waits for its `ok`.
+#[cfg(unix)]
+fn forward(path: &std::path::Path, args: &[String]) -> std::io::Result<()> {
+    use std::io::{BufRead, BufReader, Write};
+    use std::os::unix::net::UnixStream;
+
+    // The primary takes the lock before it binds the socket
+    let deadline = std::time::Instant::now() + FORWARD_TIMEOUT;
+    let mut stream = loop {
+        match UnixStream::connect(path) {
+            Ok(stream) => break stream,
+            Err(e) if std::time::Instant::now() >= deadline => return Err(e),
+            Err(_) => std::thread::sleep(CONNECT_RETRY),
+        }
+    };
+    stream.set_read_timeout(Some(FORWARD_TIMEOUT))?;
+    writeln!(stream, "{}", json!({ "args": args }))?;
+    let mut reply = String::new();
+    BufReader::new(stream).read_line(&mut reply)?;
+    if reply.trim() != "ok" {
+        return Err(std::io::Error::new(
+            std::io::ErrorKind::UnexpectedEof,
+            "no reply",
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(not(unix))]
+pub fn acquire(_args: &[String]) -> Result<Instance, Box<dyn std::error::Error>> {
+    Ok(Instance::Unguarded)
+}
+
+/// Accepts forwarded launches on a background thread and removes the socket on shutdown.
+#[cfg(unix)]
+pub fn serve(instance: Instance, app: AppHandle) {
+    use std::io::{BufRead, BufReader, Write};
+
+    let Instance::Primary { listener, lock } = instance else {
+        return;
+    };
+
+    let path = listener
+        .local_addr()
+        .ok()
+        .and_then(|addr| addr.as_pathname().map(|p| p.to_path_buf()));
+    if let Some(path) = path {
+        app.state::<crate::shutdown::ShutdownCoordinator>()
+            .register("single-instance", move || {
+                std::fs::remove_file(&path).map_err(|e| e.to_string())
+            });
+    }
+
+    std::thread::spawn(move || {
+        // Held, and so locked, for the life of the process
+        let _lock = lock;
+        for stream in listener.incoming().flatten() {
+            // Connections are handled one at a time; a silent client must not stall the rest
+            if stream.set_read_timeout(Some(FORWARD_TIMEOUT)).is_err() {
+                continue;
+            }
+            let mut line = String::new();
+            let mut reader = BufReader::new(&stream);
+            if reader.read_line(&mut line).is_err() {
+                continue;
+            }
+            let args: Vec<String> = serde_json::from_str::<serde_json::Value>(&line)
+                .ok()
+                .and_then(|v| serde_json::from_value(v["args"].clone()).ok())
+                .unwrap_or_default();
+            let _ = writeln!(&stream, "ok");
+
+            println!("📨 Second launch forwarded: {:?}", args);
+            focus_main_window(&app);
+            apply(&app, &ForwardedArgs::parse(&args));
+        }
+    });
+}
+
+#[cfg(not(unix))]
+pub fn serve(_instance: Instance, _app: AppHandle) {}
+
+#[cfg(unix)]
+fn focus_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window(crate::MAIN_WINDOW_LABEL) {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// Opens the URL and/or runs the action in the main window.
+pub fn apply(app: &AppHandle, args: &ForwardedArgs) {
+    if let Some(url) = &args.url {
+        if let Some(window) = app.get_webview_window(crate::MAIN_WINDOW_LABEL) {
+            println!("🔗 Opening {}", url);
+            if let Err(e) = window.navigate(url.clone()) {
+                eprintln!("❌ Navigate error: {}", e);
+            }
+        }
+    }
+    if let Some(action) = &args.action {
+        if let Err(e) = app.emit("ytm:command", json!({ "action": action })) {
+            eprintln!("❌ Error emitting forwarded action: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> ForwardedArgs {
+        let args: Vec<String> = args.iter().map(|a| a.to_string()).collect();
+        ForwardedArgs::parse(&args)
+    }
+
+    #[test]
+    fn action_as_separate_argument() {
+        assert_eq!(parse(&["--action", "next"]).action.as_deref(), Some("next"));
+    }
+
+    #[test]
+    fn action_with_equals() {
+        assert_eq!(
+            parse(&["--action=playPause"]).action.as_deref(),
+            Some("playPause")
+        );
+    }
+
+    #[test]
+    fn unknown_action_is_ignored() {
+        assert_eq!(parse(&["--action", "rm -rf"]), ForwardedArgs::default());
+        assert_eq!(parse(&["--action=seek"]), ForwardedArgs::default());
+        // A dangling flag has nothing to forward
+        assert_eq!(parse(&["--action"]), ForwardedArgs::default());
+    }
+
+    #[test]
+    fn music_url_is_kept() {
+        let parsed = parse(&["https://music.youtube.com/watch?v=dQw4w9WgXcQ"]);
+        assert_eq!(
+            parsed.url.map(String::from).as_deref(),
+            Some("https://music.youtube.com/watch?v=dQw4w9WgXcQ")
+        );
+        assert_eq!(parsed.action, None);
+    }
+
+    #[test]
+    fn non_music_urls_are_ignored() {
+        for url in [
+            "https://www.youtube.com/watch?v=dQw4w9WgXcQ",
+            "http://music.youtube.com/watch?v=dQw4w9WgXcQ",
+            "https://music.youtube.com.evil.example/",
+            "file:///etc/passwd",
+            "not a url",
+        ] {
+            assert_eq!(parse(&[url]).url, None, "{}", url);
+        }
+    }
+
+    #[test]
+    fn url_and_action_together() {
+        let parsed = parse(&[
+            "/usr/bin/yt-hear",
+            "--action",
+            "like",
+            "https://music.youtube.com/",
+        ]);
+        assert_eq!(parsed.action.as_deref(), Some("like"));
+        assert!(parsed.url.is_some());
+    }
+
+    #[cfg(unix)]
+    mod guard {
+        use super::super::*;
+        use std::path::{Path, PathBuf};
+        use std::process::{Command, Stdio};
+        use std::time::{Duration, Instant};
+
+        /// Set on the child processes of `concurrent_launches_elect_one_primary`.
+        const CHILD_DIR_ENV: &str = "YT_HEAR_INSTANCE_TEST_DIR";
+
+        fn test_dir(name: &str) -> PathBuf {
+            let dir = std::env::temp_dir().join(format!(
+                "yt-hear-instance-{}-{}",
+                name,
+                std::process::id()
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+            dir
+        }
+
+        /// Runs only inside a child process: acquires the guard and, as
+        /// primary, answers forwarded launches the way `serve` does.
+        #[test]
+        fn instance_child() {
+            use std::io::{BufRead, BufReader, Write};
+
+            let Some(dir) = std::env::var_os(CHILD_DIR_ENV) else {
+                return;
+            };
+            match acquire_in(Path::new(&dir), &["--action".into(), "next".into()]).unwrap() {
+                Instance::Primary { listener, .. } => {
+                    println!("outcome=primary");
+                    listener.set_nonblocking(true).unwrap();
+                    let deadline = Instant::now() + Duration::from_secs(4);
+                    while Instant::now() < deadline {
+                        let Ok((stream, _)) = listener.accept() else {
+                            std::thread::sleep(Duration::from_millis(20));
+                            continue;
+                        };
+                        stream.set_nonblocking(false).unwrap();
+                        let mut line = String::new();
+                        let _ = BufReader::new(&stream).read_line(&mut line);
+                        let _ = writeln!(&stream, "ok");
+                    }
+                }
+                Instance::Forwarded => println!("outcome=forwarded"),
+                Instance::Unresponsive => println!("outcome=unresponsive"),
+            }
+        }
+
+        #[test]
+        fn concurrent_launches_elect_one_primary() {
+            let dir = test_dir("race");
+            let children: Vec<_> = (0..2)
+                .map(|_| {
+                    Command::new(std::env::current_exe().unwrap())
+                        .args([
+                            "--exact",
+                            "single_instance::tests::guard::instance_child",
+                            "--nocapture",
+                        ])
+                        .env(CHILD_DIR_ENV, &dir)
+                        .stdout(Stdio::piped())
+                        .spawn()
+                        .unwrap()
+                })
+                .collect();
+
+            let mut outcomes: Vec<String> = children
+                .into_iter()
+                .map(|child| {
+                    let output = child.wait_with_output().unwrap();
+                    String::from_utf8_lossy(&output.stdout)
+                        .lines()
+                        .find_map(|l| l.strip_prefix("outcome="))
+                        .unwrap_or("none")
+                        .to_string()
+                })
+                .collect();
+            outcomes.sort();
+            assert_eq!(outcomes, ["forwarded", "primary"]);
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn runtime_folder_must_belong_to_this_user() {
+            let dir = test_dir("owner");
+            std::fs::create_dir_all(&dir).unwrap();
+            check_owner(&dir).unwrap();
+
+            // A symlink planted in the temp folder is refused even if it points at our folder
+            let link = test_dir("owner-link");
+            std::os::unix::fs::symlink(&dir, &link).unwrap();
+            assert!(check_owner(&link).is_err());
+            assert!(acquire_in(&link, &[]).is_err());
+            let _ = std::fs::remove_file(&link);
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+
+        #[test]
+        fn silent_primary_keeps_the_lock() {
+            let dir = test_dir("silent");
+            let primary = acquire_in(&dir, &[]).unwrap();
+            assert!(matches!(primary, Instance::Primary { .. }));
+
+            // Nobody reads the socket, so the second launch must not start anyway
+            let second = acquire_in(&dir, &["--action".into(), "next".into()]).unwrap();
+            assert!(matches!(second, Instance::Unresponsive));
+
+            // Once the primary is gone the next launch takes over its stale socket
+            drop(primary);
+            assert!(matches!(
+                acquire_in(&dir, &[]).unwrap(),
+                Instance::Primary { .. }
+            ));
+            let _ = std::fs::remove_dir_all(&dir);
+        }
+    }
+}
@@ -1,10 +1,61 @@
+use crate::adblock_plugin::AdBlockState;
+use crate::http_server::ApiListen;
+use crate::preferences::{self, CloseBehavior, PreferencesStore};
 use tauri::{
-    menu::{Menu, MenuItem, PredefinedMenuItem},
+    menu::{CheckMenuItem, Menu, MenuItem, PredefinedMenuItem, Submenu},
     tray::{MouseButton, TrayIconBuilder, TrayIconEvent},
     AppHandle, Emitter, Manager,
 };
 
+/// Handles to the tray check items so their state can follow the preferences.
+pub struct TrayToggles {
+    start_minimized: CheckMenuItem<tauri::Wry>,
+    launch_at_login: CheckMenuItem<tauri::Wry>,
+    block_ads: CheckMenuItem<tauri::Wry>,
+    close_behavior: Vec<(CloseBehavior, CheckMenuItem<tauri::Wry>)>,
+    api_listen: Vec<(ApiListen, CheckMenuItem<tauri::Wry>)>,
+}
+
+const CLOSE_BEHAVIOR_PREFIX: &str = "close_behavior:";
+const API_LISTEN_PREFIX: &str = "api_listen:";
+
+/// One check item per option, ids `<prefix><key>`; only `current` is checked.
+fn choice_items<T: Copy + PartialEq>(
+    app: &AppHandle,
+    prefix: &str,
+    options: impl IntoIterator<Item = (T, &'static str, &'static str)>,
+    current: T,
+) -> tauri::Result<Vec<(T, CheckMenuItem<tauri::Wry>)>> {
+    options
+        .into_iter()
+        .map(|(value, key, label)| {
+            CheckMenuItem::with_id(
+                app,
+                format!("{}{}", prefix, key),
+                label,
+                true,
+                value == current,
+                None::<String>,
+            )
+            .map(|item| (value, item))
+        })
+        .collect()
+}
+
+fn choice_submenu<T>(
+    app: &AppHandle,
+    title: &str,
+    items: &[(T, CheckMenuItem<tauri::Wry>)],
+) -> tauri::Result<Submenu<tauri::Wry>> {
+    let items: Vec<&dyn tauri::menu::IsMenuItem<tauri::Wry>> = items
+        .iter()
+        .map(|(_, item)| item as &dyn tauri::menu::IsMenuItem<tauri::Wry>)
+        .collect();
+    Submenu::with_items(app, title, true, &items)
+}
+
 pub fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+    let prefs = app.state::<PreferencesStore>().get();
     let show = MenuItem::with_id(app, "show", "Show", true, None::<String>)?;
     let hide = MenuItem::with_id(app, "hide", "Hide", true, None::<String>)?;
     let separator1 = PredefinedMenuItem::separator(app)?;
@@ -15,13 +66,73 @@ pub fn create_tray(app: &tauri::AppHandle) -> Result<(), Box<dyn std::error::Err
         true,
         None::<String>,
     )?;
+    // Operator switch for the session bypass; pages cannot reach it
+    let block_ads = CheckMenuItem::with_id(
+        app,
+        "block_ads",
+        "Block Ads",
+        true,
+        !app.state::<AdBlockState>().is_disabled(),
+        None::<String>,
+    )?;
     let separator2 = PredefinedMenuItem::separator(app)?;
+    let start_minimized = CheckMenuItem::with_id(
+        app,
+        "start_minimized",
+        "Start Minimized",
+        true,
+        prefs.start_minimized,
+        None::<String>,
+    )?;
+    let launch_at_login = CheckMenuItem::with_id(
+        app,
+        "launch_at_login",
+        "Launch at Login",
+        cfg!(target_os = "linux"),
+        prefs.launch_at_login,
+        None::<String>,
+    )?;
+    let close_behavior = choice_items(
+        app,
+        CLOSE_BEHAVIOR_PREFIX,
+        CloseBehavior::ALL.map(|b| (b, b.key(), b.label())),
+        prefs.close_behavior,
+    )?;
+    let close_submenu = choice_submenu(app, "When Closing", &close_behavior)?;
+    let api_listen = choice_items(
+        app,
+        API_LISTEN_PREFIX,
+        ApiListen::ALL.map(|l| (l, l.key(), l.label())),
+        prefs.api_listen,
+    )?;
+    let api_listen_submenu = choice_submenu(app, "API Listens On", &api_listen)?;
+    let separator3 = PredefinedMenuItem::separator(app)?;
     let quit = MenuItem::with_id(app, "quit", "Quit", true, None::<String>)?;
 
     let menu = Menu::with_items(
         app,
-        &[&show, &hide, &separator1, &toggle_api, &separator2, &quit],
+        &[
+            &show,
+            &hide,
+            &separator1,
+            &toggle_api,
+            &api_listen_submenu,
+            &block_ads,
+            &separator2,
+            &start_minimized,
+            &launch_at_login,
+            &close_submenu,
+            &separator3,
+            &quit,
+        ],
     )?;
+    app.manage(TrayToggles {
+        start_minimized,
+        launch_at_login,
+        block_ads,
+        close_behavior,
+        api_listen,
+    });
 
     let _tray = TrayIconBuilder::new()
         .menu(&menu)
@@ -78,9 +189,76 @@ pub fn handle_menu_event(app: &AppHandle, event: tauri::menu::MenuEvent) {
                 }
             }
         }
+        "start_minimized" => {
+            let store = app.state::<PreferencesStore>();
+            match store.update(|p| p.start_minimized = !p.start_minimized) {
+                Ok(prefs) => sync_toggles(app, &prefs),
+                Err(e) => eprintln!("⚠️ Preferences save error: {}", e),
+            }
+        }
+        "launch_at_login" => {
+            let store = app.state::<PreferencesStore>();
+            let enabled = !store.get().launch_at_login;
+            let result = preferences::autostart::set_enabled(app, enabled)
+                .and_then(|_| store.update(|p| p.launch_at_login = enabled));
+            if let Err(e) = result {
+                eprintln!("⚠️ Launch at login error: {}", e);
+            }
+            sync_toggles(app, &store.get());
+        }
+        "block_ads" => {
+            let adblock = app.state::<AdBlockState>();
+            adblock.set_disabled(!adblock.is_disabled());
+            sync_block_ads(app);
+        }
         "quit" => {
             super::cleanup_and_exit(app);
         }
-        _ => {}
+        id => {
+            let store = app.state::<PreferencesStore>();
+            let result = if let Some(behavior) = id
+                .strip_prefix(CLOSE_BEHAVIOR_PREFIX)
+                .and_then(CloseBehavior::from_key)
+            {
+                store.update(|p| p.close_behavior = behavior)
+            } else if let Some(listen) = id
+                .strip_prefix(API_LISTEN_PREFIX)
+                .and_then(ApiListen::from_key)
+            {
+                println!(
+                    "📡 API will listen on {} from its next start",
+                    listen.label()
+                );
+                store.update(|p| p.api_listen = listen)
+            } else {
+                return;
+            };
+            if let Err(e) = result {
+                eprintln!("⚠️ Preferences save error: {}", e);
+            }
+            sync_toggles(app, &store.get());
+        }
+    }
+}
+
+/// Check items flip themselves when clicked; put them back in line with what was saved.
+fn sync_toggles(app: &AppHandle, prefs: &preferences::Preferences) {
+    if let Some(toggles) = app.try_state::<TrayToggles>() {
+        let _ = toggles.start_minimized.set_checked(prefs.start_minimized);
+        let _ = toggles.launch_at_login.set_checked(prefs.launch_at_login);
+        for (behavior, item) in &toggles.close_behavior {
+            let _ = item.set_checked(prefs.close_behavior == *behavior);
+        }
+        for (listen, item) in &toggles.api_listen {
+            let _ = item.set_checked(prefs.api_listen == *listen);
+        }
+    }
+}
+
+/// Puts the "Block Ads" item in line with the session switch, which the API can also flip.
+pub fn sync_block_ads(app: &AppHandle) {
+    if let Some(toggles) = app.try_state::<TrayToggles>() {
+        let disabled = app.state::<AdBlockState>().is_disabled();
+        let _ = toggles.block_ads.set_checked(!disabled);
     }
 }
@@ -1,34 +1,307 @@
-use tauri::{AppHandle, RunEvent, WindowEvent};
+use crate::preferences::{CloseBehavior, PreferencesStore};
+use crate::shutdown::ShutdownCoordinator;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager, PhysicalPosition, PhysicalSize, RunEvent, Runtime, WindowEvent};
+
+const WINDOW_STATE_FILE: &str = "window-state.json";
+/// How much of the window must overlap a monitor to count as visible.
+const MIN_VISIBLE_PX: i32 = 64;
+
+/// Set once the app is on its way out (tray Quit, exit request, session end);
+/// from then on close requests are let through instead of hiding the window.
+static QUITTING: AtomicBool = AtomicBool::new(false);
+
+pub fn mark_quitting() {
+    QUITTING.store(true, Ordering::SeqCst);
+}
+
+/// Last known geometry of a window; position/size are the un-maximized bounds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WindowGeometry {
+    pub x: i32,
+    pub y: i32,
+    pub width: u32,
+    pub height: u32,
+    #[serde(default)]
+    pub maximized: bool,
+    #[serde(default)]
+    pub fullscreen: bool,
+    #[serde(default)]
+    pub monitor: Option<String>,
+}
+
+/// Geometry of every window, keyed by label, persisted in the app data dir.
+#[derive(Default)]
+pub struct WindowStateStore {
+    path: Mutex<Option<PathBuf>>,
+    windows: Mutex<HashMap<String, WindowGeometry>>,
+}
+
+impl WindowStateStore {
+    pub fn load(&self, app: &AppHandle) -> Result<(), Box<dyn std::error::Error>> {
+        let dir = app.path().app_data_dir()?;
+        fs::create_dir_all(&dir)?;
+        self.load_from(dir.join(WINDOW_STATE_FILE))
+    }
+
+    fn load_from(&self, path: PathBuf) -> Result<(), Box<dyn std::error::Error>> {
+        *self.path.lock().unwrap() = Some(path.clone());
+
+        if let Ok(raw) = fs::read_to_string(&path) {
+            match serde_json::from_str(&raw) {
+                Ok(windows) => *self.windows.lock().unwrap() = windows,
+                Err(e) => {
+                    // Keep the unreadable file around; the next save starts from defaults
+                    let backup = path.with_extension("json.bak");
+                    fs::rename(&path, &backup)?;
+                    return Err(format!("{} (moved to {})", e, backup.display()).into());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    pub fn save(&self) {
+        let Some(path) = self.path.lock().unwrap().clone() else {
+            return;
+        };
+        let data = match serde_json::to_string_pretty(&*self.windows.lock().unwrap()) {
+            Ok(data) => data,
+            Err(e) => return eprintln!("Window state serialize error: {}", e),
+        };
+        if let Err(e) = fs::write(&path, data) {
+            eprintln!("Window state save error: {}", e);
+        }
+    }
+
+    fn get(&self, label: &str) -> Option<WindowGeometry> {
+        self.windows.lock().unwrap().get(label).cloned()
+    }
+
+    /// Captures the current geometry of `window`. While maximized, fullscreen or
+    /// minimized only the flags change, so restoring gives back the normal bounds.
+    fn capture<R: Runtime>(&self, window: &tauri::Window<R>) {
+        let maximized = window.is_maximized().unwrap_or(false);
+        let fullscreen = window.is_fullscreen().unwrap_or(false);
+        let minimized = window.is_minimized().unwrap_or(false);
+        let monitor = window
+            .current_monitor()
+            .ok()
+            .flatten()
+            .and_then(|m| m.name().cloned());
+
+        let mut windows = self.windows.lock().unwrap();
+        let bounds = if maximized || fullscreen || minimized {
+            windows
+                .get(window.label())
+                .map(|g| (g.x, g.y, g.width, g.height))
+        } else {
+            None
+        };
+        let (x, y, width, height) = match bounds {
+            Some(bounds) => bounds,
+            None => {
+                let (Ok(position), Ok(size)) = (window.outer_position(), window.inner_size())
+                else {
+                    return;
+                };
+                if minimized {
+                    // No normal bounds recorded yet and the minimized ones are meaningless
+                    return;
+                }
+                (position.x, position.y, size.width, size.height)
+            }
+        };
+
+        windows.insert(
+            window.label().to_string(),
+            WindowGeometry {
+                x,
+                y,
+                width,
+                height,
+                maximized,
+                fullscreen,
+                monitor: monitor
+                    .or_else(|| windows.get(window.label()).and_then(|g| g.monitor.clone())),
+            },
+        );
+    }
+}
+
+/// Applies the saved geometry to a freshly created window, pulling it back
+/// on-screen when its monitor is gone or it no longer overlaps any monitor.
+pub fn restore_geometry<R: Runtime>(window: &tauri::WebviewWindow<R>) {
+    let Some(store) = window.try_state::<WindowStateStore>() else {
+        return;
+    };
+    let Some(geometry) = store.get(window.label()) else {
+        return;
+    };
+
+    let monitors = window.available_monitors().unwrap_or_default();
+    let monitor_present = geometry
+        .monitor
+        .as_ref()
+        .map(|name| monitors.iter().any(|m| m.name() == Some(name)))
+        .unwrap_or(true);
+    let on_screen = monitors
+        .iter()
+        .any(|m| overlaps(*m.position(), *m.size(), &geometry));
+
+    let _ = window.set_size(PhysicalSize::new(geometry.width, geometry.height));
+    if monitor_present && on_screen {
+        let _ = window.set_position(PhysicalPosition::new(geometry.x, geometry.y));
+    } else {
+        println!(
+            "🪟 Saved position of '{}' is off-screen, centering",
+            window.label()
+        );
+        let _ = window.center();
+    }
+
+    if geometry.fullscreen {
+        let _ = window.set_fullscreen(true);
+    } else if geometry.maximized {
+        let _ = window.maximize();
+    }
+}
+
+/// Whether at least `MIN_VISIBLE_PX` of the window in each direction falls
+/// inside the monitor at `area` with `size`.
+fn overlaps(
+    area: PhysicalPosition<i32>,
+    size: PhysicalSize<u32>,
+    geometry: &WindowGeometry,
+) -> bool {
+    let left = geometry.x.max(area.x);
+    let top = geometry.y.max(area.y);
+    let right = (geometry.x + geometry.width as i32).min(area.x + size.width as i32);
+    let bottom = (geometry.y + geometry.height as i32).min(area.y + size.height as i32);
+    right - left >= MIN_VISIBLE_PX && bottom - top >= MIN_VISIBLE_PX
+}
+
+fn save_geometry<R: Runtime>(window: &tauri::Window<R>) {
+    if let Some(store) = window.try_state::<WindowStateStore>() {
+        store.capture(window);
+        store.save();
+    }
+}
 
 pub fn handle_window_event(window: &tauri::Window, event: &WindowEvent) {
+    if let WindowEvent::Moved(_) | WindowEvent::Resized(_) = event {
+        if let Some(store) = window.try_state::<WindowStateStore>() {
+            store.capture(window);
+        }
+    }
+
     if let WindowEvent::CloseRequested { api, .. } = event {
-        // Check if this is a direct window close request (not from system shutdown)
-        if is_direct_close_request(window) {
-            // Hide to tray instead of closing
-            if let Err(e) = window.hide() {
-                eprintln!("Failed to hide window: {}", e);
-                // If hiding fails, allow the window to close
+        save_geometry(window);
+        // System shutdown/logout or an explicit quit: let the window close
+        if !is_direct_close_request() {
+            return;
+        }
+
+        let behavior = window
+            .try_state::<PreferencesStore>()
+            .map(|store| store.get().close_behavior)
+            .unwrap_or_default();
+        let result = match behavior {
+            CloseBehavior::HideToTray => window.hide(),
+            CloseBehavior::Minimize => window.minimize(),
+            CloseBehavior::Quit => {
+                mark_quitting();
                 return;
             }
-            api.prevent_close();
-        } else {
-            // Allow close for system shutdown or other non-user-initiated closes
-            // Don't prevent close in these cases
+        };
+        if let Err(e) = result {
+            // If hiding fails, allow the window to close
+            eprintln!("Failed to {:?} window: {}", behavior, e);
+            return;
+        }
+        api.prevent_close();
+
+        if behavior == CloseBehavior::HideToTray {
+            show_close_notice_once(window.app_handle());
         }
     }
 }
 
-// Helper function to determine if this is a direct user close request
-fn is_direct_close_request(_window: &tauri::Window) -> bool {
-    // In a real implementation, you might want to track the source of the close request
-    // For now, we'll assume all close requests are direct user requests
-    // This could be enhanced with additional logic if needed
-    true
+fn is_direct_close_request() -> bool {
+    !QUITTING.load(Ordering::SeqCst)
+}
+
+/// Tells the user, the first time only, that closing left the app running in the tray.
+fn show_close_notice_once(app: &AppHandle) {
+    let Some(store) = app.try_state::<PreferencesStore>() else {
+        return;
+    };
+    if store.get().close_notice_shown {
+        return;
+    }
+    if let Err(e) = store.update(|p| p.close_notice_shown = true) {
+        eprintln!("⚠️ Preferences save error: {}", e);
+    }
+
+    let body = "Still running in the tray. Use the tray menu to quit or change what the close button does.";
+    println!("🔔 {}", body);
+    desktop_notify(&app.package_info().name, body);
 }
 
+#[cfg(target_os = "linux")]
+fn desktop_notify(title: &str, body: &str) {
+    if let Err(e) = std::process::Command::new("notify-send")
+        .args(["--app-name", title, title, body])
+        .spawn()
+    {
+        eprintln!("⚠️ Close notice error: {}", e);
+    }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn desktop_notify(_title: &str, _body: &str) {}
+
+/// Quits cleanly when the session ends (logout/shutdown send SIGTERM or SIGHUP)
+/// instead of holding the window open and blocking the session.
+#[cfg(unix)]
+pub fn listen_for_session_end(app: AppHandle) {
+    use tokio::signal::unix::{signal, SignalKind};
+
+    tauri::async_runtime::spawn(async move {
+        let (Ok(mut term), Ok(mut hup)) = (
+            signal(SignalKind::terminate()),
+            signal(SignalKind::hangup()),
+        ) else {
+            eprintln!("⚠️ Could not listen for session end signals");
+            return;
+        };
+        tokio::select! {
+            _ = term.recv() => println!("🛑 SIGTERM received, quitting"),
+            _ = hup.recv() => println!("🛑 SIGHUP received, quitting"),
+        }
+        mark_quitting();
+        super::cleanup_and_exit(&app);
+    });
+}
+
+#[cfg(not(unix))]
+pub fn listen_for_session_end(_app: AppHandle) {}
+
 pub fn handle_run_event(app_handle: &AppHandle, event: &RunEvent) -> bool {
     match event {
-        RunEvent::ExitRequested { .. } => {
+        RunEvent::ExitRequested { api, .. } => {
+            // Exits not started by the coordinator are held until it has run
+            let coordinator = app_handle.state::<ShutdownCoordinator>();
+            if !coordinator.is_started() {
+                api.prevent_exit();
+                super::cleanup_and_exit(app_handle);
+                return false;
+            }
             // Allow the app to exit properly when requested
             // Don't prevent exit, let it happen naturally
             return true;
@@ -48,3 +321,84 @@ pub fn handle_run_event(app_handle: &AppHandle, event: &RunEvent) -> bool {
     }
     false
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn geometry(x: i32, y: i32, width: u32, height: u32) -> WindowGeometry {
+        WindowGeometry {
+            x,
+            y,
+            width,
+            height,
+            maximized: false,
+            fullscreen: false,
+            monitor: None,
+        }
+    }
+
+    fn on_1080p(g: &WindowGeometry) -> bool {
+        overlaps(
+            PhysicalPosition::new(0, 0),
+            PhysicalSize::new(1920, 1080),
+            g,
+        )
+    }
+
+    #[test]
+    fn window_inside_the_monitor_is_visible() {
+        assert!(on_1080p(&geometry(100, 100, 800, 600)));
+    }
+
+    #[test]
+    fn window_past_any_edge_is_off_screen() {
+        assert!(!on_1080p(&geometry(2000, 100, 800, 600)));
+        assert!(!on_1080p(&geometry(-900, 100, 800, 600)));
+        assert!(!on_1080p(&geometry(100, 1200, 800, 600)));
+        assert!(!on_1080p(&geometry(100, -700, 800, 600)));
+    }
+
+    #[test]
+    fn sliver_below_the_threshold_is_off_screen() {
+        let edge = 1920 - MIN_VISIBLE_PX;
+        assert!(on_1080p(&geometry(edge, 100, 800, 600)));
+        assert!(!on_1080p(&geometry(edge + 1, 100, 800, 600)));
+    }
+
+    #[test]
+    fn secondary_monitor_uses_its_own_origin() {
+        let g = geometry(2000, 100, 800, 600);
+        assert!(overlaps(
+            PhysicalPosition::new(1920, 0),
+            PhysicalSize::new(2560, 1440),
+            &g,
+        ));
+        assert!(!overlaps(
+            PhysicalPosition::new(-1920, 0),
+            PhysicalSize::new(1920, 1080),
+            &g,
+        ));
+    }
+
+    #[test]
+    fn unreadable_state_file_is_backed_up() {
+        let dir = std::env::temp_dir().join(format!("yt-hear-window-test-{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join(WINDOW_STATE_FILE);
+        fs::write(&path, "{ not json").unwrap();
+
+        let store = WindowStateStore::default();
+        assert!(store.load_from(path.clone()).is_err());
+        assert!(!path.exists());
+        assert_eq!(
+            fs::read_to_string(path.with_extension("json.bak")).unwrap(),
+            "{ not json"
+        );
+
+        // Saving now writes a fresh file instead of touching the backup
+        store.save();
+        assert!(path.exists());
+        let _ = fs::remove_dir_all(&dir);
+    }
+}